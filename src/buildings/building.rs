@@ -2,14 +2,14 @@ use bevy::{ecs::system::EntityCommands, prelude::*, window::PrimaryWindow};
 
 use crate::{
     SpriteLoader,
-    buildings::Crafter,
+    buildings::{BuildingHealth, Crafter},
     data::{BuildingId, RecipeId},
     items::{Inventory, RecipeOutputs},
     universe::{Asteroid, Astre, DockableOnAstre, SHIP_Z},
 };
 
 const BUILDING_PREVIEW_Z: f32 = SHIP_Z - 1.0;
-const BUILDING_SCALE: f32 = 3.0;
+pub const BUILDING_SCALE: f32 = 3.0;
 const PLACING_ZONES_COLOR: Color = Color::srgba(0.5, 0.8, 0.8, 0.5);
 const HIGHLIGHT_COLOR: Color = Color::srgb(0.0, 1.0, 1.0);
 
@@ -28,6 +28,20 @@ impl BuildingData {
     pub fn sprite_path(&self) -> String {
         format!("sprites/{}.png", self.sprite_name)
     }
+
+    // Turns the entity into the finished building, as when its construction site is done
+    pub fn build(&self, ec: &mut EntityCommands, transform: Transform) {
+        ec.insert((
+            BuildingHighlight,
+            SpriteLoader {
+                texture_path: self.sprite_path(),
+                ..default()
+            },
+            transform,
+        ));
+
+        (self.on_build)(ec);
+    }
 }
 
 #[derive(Clone, Copy, Reflect, Default, Debug)]
@@ -36,15 +50,97 @@ pub enum LocationOnAstre {
     Atmosphere,
     SurfaceOrAtmosphere,
     CloseOrbit,
+    SurfaceOrCloseOrbit,
     #[default]
     Anywhere,
 }
 
+impl LocationOnAstre {
+    // Whether a building can stand at this distance from the center of the astre
+    pub fn allows(self, astre: &Astre, distance: f32) -> bool {
+        match self {
+            LocationOnAstre::Surface => distance < astre.surface_radius(),
+            LocationOnAstre::Atmosphere => {
+                distance < astre.atmosphere_radius() && distance > astre.surface_radius()
+            }
+            LocationOnAstre::SurfaceOrAtmosphere => distance < astre.atmosphere_radius(),
+            LocationOnAstre::CloseOrbit => {
+                distance < astre.close_orbit_radius() && distance > astre.atmosphere_radius()
+            }
+            LocationOnAstre::SurfaceOrCloseOrbit => {
+                distance < astre.surface_radius()
+                    || (distance < astre.close_orbit_radius()
+                        && distance > astre.atmosphere_radius())
+            }
+            LocationOnAstre::Anywhere => distance < astre.close_orbit_radius(),
+        }
+    }
+}
+
 #[derive(Component)]
 pub struct BuildingPreview;
 
+// Triggered when a finished building is spawned, by a construction site or placed directly
+#[derive(Event)]
+pub struct BuildingBuilt {
+    pub building: Entity,
+    pub id: BuildingId,
+}
+
+// Spawns a finished building on the astre, at a position relative to it
+pub fn place_building(
+    world: &mut World,
+    astre: Entity,
+    building_id: BuildingId,
+    position: Vec2,
+) -> Entity {
+    let mut commands = world.commands();
+    let mut ec = commands.spawn(ChildOf(astre));
+    building_id
+        .data()
+        .build(&mut ec, Transform::from_translation(position.extend(0.)));
+    let entity = ec.id();
+
+    world.flush();
+    world.trigger(BuildingBuilt {
+        building: entity,
+        id: building_id,
+    });
+    entity
+}
+
+// Recipe turning the construction site of a building into the building
+pub fn construction_recipe(building_id: BuildingId) -> Option<RecipeId> {
+    RecipeId::ALL
+        .iter()
+        .find(|recipe_id| match recipe_id.data().outputs() {
+            RecipeOutputs::Building(output) => output == building_id,
+            _ => false,
+        })
+        .copied()
+}
+
+// Construction site waiting for the inputs of its recipe
+pub fn build_construction_site(
+    building_id: BuildingId,
+    recipe_id: RecipeId,
+    transform: Transform,
+) -> impl Bundle {
+    (
+        SpriteLoader {
+            texture_path: building_id.data().sprite_path(),
+            color: Color::default().with_alpha(0.8),
+        },
+        transform,
+        BuildingHighlight,
+        Crafter::new_construction_site(vec![recipe_id]),
+        Inventory::new(recipe_id.data().inputs_quantity()),
+    )
+}
+
 #[derive(Component, Reflect, Default)]
 #[reflect(Component, Default)]
+#[require(BuildingHealth)]
 pub struct BuildingHighlight;
 
 pub fn spawn_building(
@@ -83,29 +179,15 @@ pub fn spawn_building(
 
             // Place construction site
             if left {
-                if let Some(recipe_id) =
-                    RecipeId::ALL
-                        .iter()
-                        .find(|recipe_id| match recipe_id.data().outputs() {
-                            RecipeOutputs::Building(building_id) => {
-                                building_id == placing_building.0
-                            }
-                            _ => false,
-                        })
-                {
-                    let recipe_needed_space = recipe_id.data().inputs_quantity();
-
+                if let Some(recipe_id) = construction_recipe(placing_building.0) {
                     // spawn the construction site at building_preview_transform
                     commands.spawn((
-                        SpriteLoader {
-                            texture_path: format!("sprites/{}.png", building.sprite_name),
-                            color: Color::default().with_alpha(0.8),
-                        },
-                        *building_preview_transform,
-                        BuildingHighlight,
+                        build_construction_site(
+                            placing_building.0,
+                            recipe_id,
+                            *building_preview_transform,
+                        ),
                         DockableOnAstre::instant_location(building.location),
-                        Crafter::new_construction_site(vec![*recipe_id]),
-                        Inventory::new(recipe_needed_space),
                     ));
 
                     commands.entity(building_preview_entity).despawn();
@@ -149,7 +231,9 @@ pub fn draw_placing_zones(
     for (astre, global_transform, _) in q_astres.iter().filter(|(_, _, v)| v.get()) {
         if matches!(
             location,
-            LocationOnAstre::Surface | LocationOnAstre::SurfaceOrAtmosphere
+            LocationOnAstre::Surface
+                | LocationOnAstre::SurfaceOrAtmosphere
+                | LocationOnAstre::SurfaceOrCloseOrbit
         ) {
             gizmos.circle_2d(
                 global_transform.translation().truncate(),
@@ -171,7 +255,10 @@ pub fn draw_placing_zones(
             );
         }
 
-        if matches!(location, LocationOnAstre::CloseOrbit) {
+        if matches!(
+            location,
+            LocationOnAstre::CloseOrbit | LocationOnAstre::SurfaceOrCloseOrbit
+        ) {
             gizmos.circle_2d(
                 global_transform.translation().truncate(),
                 astre.close_orbit_radius(),
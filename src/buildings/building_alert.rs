@@ -0,0 +1,152 @@
+use std::fmt;
+
+use bevy::{platform::collections::HashSet, prelude::*};
+
+use crate::{
+    buildings::{BuildingDisabled, BuildingHighlight, Crafter, Extractor, LogisticFreight},
+    items::{CanCraftResult, Inventory, LogisticProvider, LogisticRequest},
+    ui::NotificationEvent,
+};
+
+// A problem is only raised as an alert after lasting this long (seconds)
+pub const ALERT_DELAY: f32 = 60.;
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum AlertKind {
+    MissingInputs,
+    OutputBlocked,
+    InventoryFull,
+    UnfulfilledRequest,
+    FreightIdle,
+}
+
+impl fmt::Display for AlertKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AlertKind::MissingInputs => write!(f, "missing inputs"),
+            AlertKind::OutputBlocked => write!(f, "no space for the outputs"),
+            AlertKind::InventoryFull => write!(f, "inventory full"),
+            AlertKind::UnfulfilledRequest => write!(f, "no freight for its request"),
+            AlertKind::FreightIdle => write!(f, "no journey while items are requested"),
+        }
+    }
+}
+
+// Problem of a building, tracked until it is solved. Not saved: tracking starts again on load.
+#[derive(Component, Debug)]
+pub struct BuildingAlert {
+    kind: AlertKind,
+    duration: f32, // seconds
+}
+
+impl BuildingAlert {
+    pub fn kind(&self) -> AlertKind {
+        self.kind
+    }
+
+    pub fn is_raised(&self) -> bool {
+        self.duration >= ALERT_DELAY
+    }
+}
+
+pub fn detect_stalled_buildings(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut q_buildings: Query<
+        (
+            Entity,
+            &Inventory,
+            Option<&Crafter>,
+            Has<Extractor>,
+            Has<LogisticProvider>,
+            Option<&LogisticRequest>,
+            Option<&LogisticFreight>,
+            Has<BuildingDisabled>,
+            Option<&mut BuildingAlert>,
+            Option<&Name>,
+        ),
+        With<BuildingHighlight>,
+    >,
+    q_parents: Query<&ChildOf>,
+) {
+    // Solar systems where freights have something to do
+    let unfulfilled_solar_systems = q_buildings
+        .iter()
+        .filter(|(.., request, _, _, _, _)| {
+            request.is_some_and(|request| request.freights.is_empty())
+        })
+        .map(|(entity, ..)| q_parents.root_ancestor(entity))
+        .collect::<HashSet<_>>();
+
+    for (
+        entity,
+        inventory,
+        crafter,
+        is_extractor,
+        is_provider,
+        request,
+        freight,
+        disabled,
+        alert,
+        name,
+    ) in &mut q_buildings
+    {
+        let unfulfilled_request = request.is_some_and(|request| request.freights.is_empty());
+
+        let crafter_problem = crafter
+            .and_then(Crafter::recipe)
+            .and_then(|recipe| match inventory.can_craft(recipe) {
+                CanCraftResult::Yes => None,
+                CanCraftResult::MissingInputs(_) if unfulfilled_request => {
+                    Some(AlertKind::UnfulfilledRequest)
+                }
+                CanCraftResult::MissingInputs(_) => Some(AlertKind::MissingInputs),
+                CanCraftResult::NotEnoughSpace => Some(AlertKind::OutputBlocked),
+            });
+
+        // Infinite inventories have a size of 0
+        let inventory_full = (is_extractor || is_provider)
+            && inventory.size() > 0
+            && inventory.remaining_space() == 0;
+
+        let freight_idle = freight.is_some_and(|freight| {
+            freight.logistic_journey().is_none()
+                && unfulfilled_solar_systems.contains(&q_parents.root_ancestor(entity))
+        });
+
+        let problem = if disabled {
+            None
+        } else if crafter.is_some() {
+            crafter_problem
+        } else if unfulfilled_request {
+            Some(AlertKind::UnfulfilledRequest)
+        } else if inventory_full {
+            Some(AlertKind::InventoryFull)
+        } else if freight_idle {
+            Some(AlertKind::FreightIdle)
+        } else {
+            None
+        };
+
+        match (problem, alert) {
+            (None, None) => {}
+            (None, Some(_)) => {
+                commands.entity(entity).remove::<BuildingAlert>();
+            }
+            (Some(kind), Some(mut alert)) if alert.kind == kind => {
+                let was_raised = alert.is_raised();
+                alert.duration += time.delta_secs();
+
+                if alert.is_raised() && !was_raised {
+                    let name = name.map_or("A building", Name::as_str);
+                    commands.trigger(NotificationEvent(format!("{name}: {kind}")));
+                }
+            }
+            (Some(kind), _) => {
+                commands
+                    .entity(entity)
+                    .insert(BuildingAlert { kind, duration: 0. });
+            }
+        }
+    }
+}
@@ -0,0 +1,184 @@
+use bevy::{
+    ecs::{entity::MapEntities, reflect::ReflectMapEntities},
+    prelude::*,
+};
+
+use crate::{
+    data::ItemId,
+    items::{Inventory, ItemMap, LogisticRequest, LogisticScope},
+    ui::NotificationEvent,
+};
+
+#[derive(PartialEq, Eq, Clone, Copy, Reflect, Default, Debug)]
+pub enum ConditionComparison {
+    #[default]
+    Above,
+    Below,
+}
+
+impl std::fmt::Display for ConditionComparison {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConditionComparison::Above => write!(f, ">"),
+            ConditionComparison::Below => write!(f, "<"),
+        }
+    }
+}
+
+// The building only works while the quantity of an item in another inventory
+// is above or below a threshold, e.g. Quarries throttled by the Warehouse they fill
+#[derive(Component, MapEntities, Reflect, Debug)]
+#[reflect(Component, Default, MapEntities)]
+pub struct BuildingCondition {
+    #[entities]
+    pub inventory: Entity,
+    pub item: ItemId,
+    pub comparison: ConditionComparison,
+    pub threshold: u32,
+}
+
+impl Default for BuildingCondition {
+    fn default() -> Self {
+        Self {
+            inventory: Entity::PLACEHOLDER,
+            item: ItemId::default(),
+            comparison: ConditionComparison::default(),
+            threshold: 0,
+        }
+    }
+}
+
+impl BuildingCondition {
+    // Parses "<building name> <item> <|> <threshold>", the building being named
+    // in the same solar system. An empty input means no condition.
+    pub fn parse(world: &mut World, building: Entity, input: &str) -> Result<Option<Self>, String> {
+        if input.is_empty() {
+            return Ok(None);
+        }
+
+        let mut words = input.rsplitn(4, ' ');
+        let (Some(threshold), Some(comparison), Some(item), Some(name)) =
+            (words.next(), words.next(), words.next(), words.next())
+        else {
+            return Err("expected <building name> <item> <|> <threshold>".to_string());
+        };
+
+        let threshold = threshold
+            .parse()
+            .map_err(|_| format!("invalid threshold {threshold}"))?;
+        let comparison = match comparison {
+            ">" => ConditionComparison::Above,
+            "<" => ConditionComparison::Below,
+            _ => return Err(format!("invalid comparison {comparison}, expected < or >")),
+        };
+        let item = ItemId::ALL
+            .iter()
+            .find(|id| format!("{id:?}") == item)
+            .copied()
+            .ok_or_else(|| format!("unknown item {item}"))?;
+
+        let solar_system = root_ancestor(world, building);
+        let (inventory, _) = world
+            .query_filtered::<(Entity, &Name), With<Inventory>>()
+            .iter(world)
+            .find(|(entity, n)| n.as_str() == name && root_ancestor(world, *entity) == solar_system)
+            .ok_or_else(|| format!("no building named {name} in this solar system"))?;
+
+        Ok(Some(Self {
+            inventory,
+            item,
+            comparison,
+            threshold,
+        }))
+    }
+
+    pub fn is_met(&self, inventory: &Inventory) -> bool {
+        let quantity = inventory.quantity(self.item);
+
+        match self.comparison {
+            ConditionComparison::Above => quantity > self.threshold,
+            ConditionComparison::Below => quantity < self.threshold,
+        }
+    }
+}
+
+fn root_ancestor(world: &World, mut entity: Entity) -> Entity {
+    while let Some(child_of) = world.get::<ChildOf>(entity) {
+        entity = child_of.parent();
+    }
+    entity
+}
+
+// Replaces the condition of a building with the one parsed from the player's input
+pub struct SetBuildingCondition(pub Entity, pub String);
+
+impl Command for SetBuildingCondition {
+    fn apply(self, world: &mut World) {
+        let SetBuildingCondition(building, input) = self;
+
+        match BuildingCondition::parse(world, building, &input) {
+            Ok(Some(condition)) => {
+                world.entity_mut(building).insert(condition);
+            }
+            Ok(None) => {
+                world.entity_mut(building).remove::<BuildingCondition>();
+            }
+            Err(e) => {
+                world.trigger(NotificationEvent(format!("Invalid condition: {e}")));
+            }
+        }
+    }
+}
+
+// Buildings whose condition is not met: their timers are paused,
+// they don't request items and they don't provide any
+#[derive(Component, Reflect, Default)]
+#[reflect(Component, Default)]
+pub struct BuildingDisabled {
+    // Request of the building when it was disabled, restored when it is enabled again
+    request: Option<(ItemMap, LogisticScope)>,
+}
+
+pub fn update_building_conditions(
+    mut commands: Commands,
+    q_conditions: Query<(
+        Entity,
+        &BuildingCondition,
+        Option<&BuildingDisabled>,
+        Option<&LogisticRequest>,
+    )>,
+    q_unconditioned: Query<(Entity, &BuildingDisabled), Without<BuildingCondition>>,
+    q_inventories: Query<&Inventory>,
+) {
+    for (entity, condition, disabled, request) in &q_conditions {
+        // A condition on a destroyed building doesn't block anything
+        let is_met = q_inventories
+            .get(condition.inventory)
+            .ok()
+            .is_none_or(|inventory| condition.is_met(inventory));
+
+        if is_met && let Some(disabled) = disabled {
+            enable_building(&mut commands, entity, disabled);
+        } else if !is_met && disabled.is_none() {
+            commands
+                .entity(entity)
+                .insert(BuildingDisabled {
+                    request: request.map(|request| (request.items().clone(), *request.scope())),
+                })
+                .remove::<LogisticRequest>();
+        }
+    }
+
+    for (entity, disabled) in &q_unconditioned {
+        enable_building(&mut commands, entity, disabled);
+    }
+}
+
+fn enable_building(commands: &mut Commands, entity: Entity, disabled: &BuildingDisabled) {
+    let mut entity = commands.entity(entity);
+    entity.remove::<BuildingDisabled>();
+
+    if let Some((items, scope)) = &disabled.request {
+        entity.insert(LogisticRequest::new(items.clone(), *scope));
+    }
+}
@@ -0,0 +1,137 @@
+use bevy::prelude::*;
+
+use crate::{
+    SpriteLoader,
+    buildings::{BuildingHighlight, repair_logistic_references},
+    items::Inventory,
+    ui::NotificationEvent,
+};
+
+pub const BUILDING_MAX_HEALTH: f32 = 100.;
+pub const HEALTH_PER_ASTRIUM: f32 = 5.;
+
+// Damaged buildings work slower, down to this ratio of their normal speed
+const BUILDING_MIN_EFFICIENCY: f32 = 0.25;
+
+const WRECK_COLOR: Color = Color::srgb(0.3, 0.3, 0.3);
+
+#[derive(Component, Reflect)]
+#[reflect(Component, Default)]
+pub struct BuildingHealth {
+    health: f32,
+}
+
+impl Default for BuildingHealth {
+    fn default() -> Self {
+        Self {
+            health: BUILDING_MAX_HEALTH,
+        }
+    }
+}
+
+impl BuildingHealth {
+    pub fn health(&self) -> f32 {
+        self.health
+    }
+
+    pub fn missing_health(&self) -> f32 {
+        BUILDING_MAX_HEALTH - self.health
+    }
+
+    pub fn is_destroyed(&self) -> bool {
+        self.health <= 0.
+    }
+
+    pub fn damage(&mut self, amount: f32) {
+        self.health = (self.health - amount).max(0.);
+    }
+
+    pub fn repair(&mut self, amount: f32) {
+        self.health = (self.health + amount).min(BUILDING_MAX_HEALTH);
+    }
+
+    // Ratio applied to the time of the building's work
+    pub fn efficiency(&self) -> f32 {
+        (self.health / BUILDING_MAX_HEALTH).max(BUILDING_MIN_EFFICIENCY)
+    }
+}
+
+// Remains of a destroyed building, holding part of its inventory until emptied
+#[derive(Component, Reflect, Default)]
+#[reflect(Component, Default)]
+#[require(Inventory)]
+pub struct Wreck;
+
+pub fn destroy_buildings(
+    mut commands: Commands,
+    q_buildings: Query<(Entity, &BuildingHealth), With<BuildingHighlight>>,
+) {
+    for (entity, health) in &q_buildings {
+        if health.is_destroyed() {
+            commands.queue(DestroyBuilding(entity));
+        }
+    }
+}
+
+// Replaces a building by a Wreck with half of its inventory, then drops the logistic references to it
+pub struct DestroyBuilding(pub Entity);
+
+impl Command for DestroyBuilding {
+    fn apply(self, world: &mut World) {
+        let Ok(entity) = world.get_entity(self.0) else {
+            return;
+        };
+
+        let (Some(transform), Some(child_of)) = (
+            entity.get::<Transform>().copied(),
+            entity.get::<ChildOf>().map(ChildOf::parent),
+        ) else {
+            return;
+        };
+
+        let mut wreck_inventory = Inventory::new(0);
+        if let Some(inventory) = entity.get::<Inventory>() {
+            for id in inventory.all_ids() {
+                let quantity = inventory.quantity(id) / 2;
+                if quantity > 0 {
+                    wreck_inventory.try_add(id, quantity);
+                }
+            }
+        }
+
+        let texture_path = entity
+            .get::<SpriteLoader>()
+            .map(|sprite| sprite.texture_path.clone())
+            .unwrap_or_default();
+
+        world.entity_mut(self.0).despawn();
+
+        world.spawn((
+            Name::new("Wreck"),
+            Wreck,
+            wreck_inventory,
+            SpriteLoader {
+                texture_path,
+                color: WRECK_COLOR,
+            },
+            transform,
+            ChildOf(child_of),
+        ));
+
+        // Freights registered to the building, or the building itself if it was a freight
+        repair_logistic_references(world);
+
+        world.trigger(NotificationEvent("A building was destroyed".to_string()));
+    }
+}
+
+pub fn despawn_empty_wrecks(
+    mut commands: Commands,
+    q_wrecks: Query<(Entity, &Inventory), (With<Wreck>, Changed<Inventory>)>,
+) {
+    for (entity, inventory) in &q_wrecks {
+        if inventory.all_ids().is_empty() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
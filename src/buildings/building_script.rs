@@ -0,0 +1,272 @@
+use std::sync::{Arc, Mutex};
+
+use bevy::{platform::collections::HashMap, prelude::*};
+use rhai::{Engine, EvalAltResult, Scope, module_resolvers::DummyModuleResolver};
+
+use crate::{
+    buildings::{BuildingDisabled, Crafter},
+    data::{ItemId, RecipeId},
+    items::{Inventory, ItemMap, LogisticRequest, LogisticScope},
+    ui::NotificationEvent,
+};
+
+const SCRIPT_INTERVAL: f32 = 1.; // seconds between two runs of a script
+const SCRIPT_MAX_OPERATIONS: u64 = 10_000;
+const SCRIPT_MAX_STRING_SIZE: usize = 1000;
+const SCRIPT_MAX_ARRAY_SIZE: usize = 100;
+
+// Rhai script run at a fixed rate. It can read the inventories of the named buildings
+// of its solar system, and only change its own building. It is paused while the building is disabled.
+#[derive(Component, Reflect)]
+#[reflect(Component, Default)]
+pub struct BuildingScript {
+    source: String,
+    cooldown: Timer,
+    last_error: Option<String>,
+}
+
+impl Default for BuildingScript {
+    fn default() -> Self {
+        Self {
+            source: String::new(),
+            cooldown: Timer::from_seconds(SCRIPT_INTERVAL, TimerMode::Repeating),
+            last_error: None,
+        }
+    }
+}
+
+impl BuildingScript {
+    pub fn new(source: String) -> Self {
+        Self {
+            source,
+            ..default()
+        }
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn last_error(&self) -> Option<&str> {
+        self.last_error.as_deref()
+    }
+}
+
+// Changes asked by a script, applied to its building after the run
+#[derive(Clone, Debug)]
+enum ScriptAction {
+    SetRecipe(RecipeId),
+    Request(ItemId, u32),
+    ClearRequest,
+}
+
+// `building` variable of the scripts
+#[derive(Clone)]
+struct ScriptBuilding {
+    inventory: ItemMap,
+    is_crafter: bool,
+    named_inventories: Arc<HashMap<String, ItemMap>>,
+    actions: Arc<Mutex<Vec<ScriptAction>>>,
+}
+
+impl ScriptBuilding {
+    fn push(&self, action: ScriptAction) {
+        self.actions.lock().unwrap().push(action);
+    }
+
+    // Crafters request the missing inputs of their recipe themselves
+    fn check_can_request(&self) -> Result<(), Box<EvalAltResult>> {
+        if self.is_crafter {
+            return Err("Crafters request their missing inputs themselves".into());
+        }
+        Ok(())
+    }
+}
+
+fn parse_item(item: &str) -> Result<ItemId, Box<EvalAltResult>> {
+    ItemId::ALL
+        .iter()
+        .find(|id| format!("{id:?}") == item)
+        .copied()
+        .ok_or_else(|| format!("Unknown item {item}").into())
+}
+
+fn parse_recipe(recipe: &str) -> Result<RecipeId, Box<EvalAltResult>> {
+    RecipeId::ALL
+        .iter()
+        .find(|id| format!("{id:?}") == recipe)
+        .copied()
+        .ok_or_else(|| format!("Unknown recipe {recipe}").into())
+}
+
+// Sandboxed engine: no access to the file system, and bounded scripts
+#[derive(Resource)]
+pub struct ScriptEngine(Engine);
+
+impl Default for ScriptEngine {
+    fn default() -> Self {
+        let mut engine = Engine::new();
+
+        // Engine::new() resolves `import` from the file system
+        engine
+            .set_module_resolver(DummyModuleResolver::new())
+            .set_max_operations(SCRIPT_MAX_OPERATIONS)
+            .set_max_string_size(SCRIPT_MAX_STRING_SIZE)
+            .set_max_array_size(SCRIPT_MAX_ARRAY_SIZE)
+            .set_max_map_size(SCRIPT_MAX_ARRAY_SIZE)
+            .on_print(|text| info!("Script: {text}"))
+            .on_debug(|text, _, _| debug!("Script: {text}"));
+
+        engine
+            .register_type_with_name::<ScriptBuilding>("Building")
+            .register_fn(
+                "quantity",
+                |building: &mut ScriptBuilding, item: &str| -> Result<i64, Box<EvalAltResult>> {
+                    let id = parse_item(item)?;
+                    Ok(building.inventory.get(&id).copied().unwrap_or(0).into())
+                },
+            )
+            .register_fn(
+                "quantity_in",
+                |building: &mut ScriptBuilding,
+                 name: &str,
+                 item: &str|
+                 -> Result<i64, Box<EvalAltResult>> {
+                    let id = parse_item(item)?;
+                    let inventory = building
+                        .named_inventories
+                        .get(name)
+                        .ok_or_else(|| format!("No building named {name}"))?;
+                    Ok(inventory.get(&id).copied().unwrap_or(0).into())
+                },
+            )
+            .register_fn(
+                "set_recipe",
+                |building: &mut ScriptBuilding, recipe: &str| -> Result<(), Box<EvalAltResult>> {
+                    building.push(ScriptAction::SetRecipe(parse_recipe(recipe)?));
+                    Ok(())
+                },
+            )
+            .register_fn(
+                "request",
+                |building: &mut ScriptBuilding,
+                 item: &str,
+                 quantity: i64|
+                 -> Result<(), Box<EvalAltResult>> {
+                    building.check_can_request()?;
+                    let quantity = u32::try_from(quantity)
+                        .map_err(|_| format!("Invalid quantity {quantity}"))?;
+                    building.push(ScriptAction::Request(parse_item(item)?, quantity));
+                    Ok(())
+                },
+            )
+            .register_fn(
+                "clear_request",
+                |building: &mut ScriptBuilding| -> Result<(), Box<EvalAltResult>> {
+                    building.check_can_request()?;
+                    building.push(ScriptAction::ClearRequest);
+                    Ok(())
+                },
+            );
+
+        Self(engine)
+    }
+}
+
+pub fn run_building_scripts(
+    mut commands: Commands,
+    time: Res<Time>,
+    engine: Res<ScriptEngine>,
+    mut q_scripts: Query<(Entity, &mut BuildingScript), Without<BuildingDisabled>>,
+    q_inventories: Query<(Entity, &Inventory, Option<&Name>)>,
+    mut q_crafters: Query<&mut Crafter>,
+    q_logistic_requests: Query<&LogisticRequest>,
+    q_parents: Query<&ChildOf>,
+) {
+    for (entity, mut script) in &mut q_scripts {
+        if !script.cooldown.tick(time.delta()).just_finished() || script.source.is_empty() {
+            continue;
+        }
+
+        let Ok((_, inventory, _)) = q_inventories.get(entity) else {
+            continue;
+        };
+
+        let solar_system = q_parents.root_ancestor(entity);
+
+        let named_inventories = q_inventories
+            .iter()
+            .filter(|(e, ..)| q_parents.root_ancestor(*e) == solar_system)
+            .filter_map(|(_, inventory, name)| {
+                name.map(|name| (name.to_string(), inventory.items().clone()))
+            })
+            .collect();
+
+        let building = ScriptBuilding {
+            inventory: inventory.items().clone(),
+            is_crafter: q_crafters.contains(entity),
+            named_inventories: Arc::new(named_inventories),
+            actions: Arc::new(Mutex::new(vec![])),
+        };
+
+        let mut scope = Scope::new();
+        scope.push("building", building.clone());
+
+        let result = engine
+            .0
+            .run_with_scope(&mut scope, &script.source)
+            .map_err(|e| e.to_string());
+
+        match result {
+            Ok(()) => script.last_error = None,
+            Err(e) => {
+                if script.last_error.as_ref() != Some(&e) {
+                    commands.trigger(NotificationEvent(format!("Script error: {e}")));
+                }
+                script.last_error = Some(e);
+                continue;
+            }
+        }
+
+        // Requests are built from all the request() calls of the run
+        let mut request = None;
+
+        for action in building.actions.lock().unwrap().drain(..) {
+            match action {
+                ScriptAction::SetRecipe(recipe) => {
+                    if let Ok(mut crafter) = q_crafters.get_mut(entity)
+                        && crafter.possible_recipes().contains(&recipe)
+                        && crafter.recipe() != Some(recipe)
+                    {
+                        crafter.set_recipe(recipe);
+                    }
+                }
+                ScriptAction::Request(id, quantity) => {
+                    *request
+                        .get_or_insert_with(ItemMap::default)
+                        .entry(id)
+                        .or_default() += quantity;
+                }
+                ScriptAction::ClearRequest => {
+                    request = Some(ItemMap::default());
+                }
+            }
+        }
+
+        let Some(items) = request else {
+            continue;
+        };
+
+        let current = q_logistic_requests.get(entity).ok();
+
+        if items.is_empty() {
+            if current.is_some() {
+                commands.entity(entity).remove::<LogisticRequest>();
+            }
+        } else if current.is_none_or(|current| current.items() != &items) {
+            commands
+                .entity(entity)
+                .insert(LogisticRequest::new(items, LogisticScope::Planet));
+        }
+    }
+}
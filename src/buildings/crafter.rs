@@ -1,10 +1,10 @@
 use bevy::prelude::*;
 
 use crate::{
-    SpriteLoader,
-    buildings::BuildingHighlight,
+    buildings::{BuildingBuilt, BuildingDisabled, BuildingHealth},
     data::RecipeId,
-    items::{CanCraftResult, Inventory, LogisticRequest, LogisticScope},
+    items::{CanCraftResult, Inventory, LogisticRequest, LogisticScope, RecipeOutputs},
+    statistics::{FlowKind, ItemFlow},
 };
 
 #[derive(Component, Reflect, Default)]
@@ -43,6 +43,10 @@ impl Crafter {
         self.recipe = Some(CrafterRecipe::new(recipe));
     }
 
+    pub fn recipe(&self) -> Option<RecipeId> {
+        self.recipe.as_ref().map(|recipe| recipe.recipe)
+    }
+
     pub fn possible_recipes(&self) -> &Vec<RecipeId> {
         &self.possible_recipes
     }
@@ -68,21 +72,34 @@ impl CrafterRecipe {
     }
 }
 
+// Triggered each time a crafter completes its recipe
+#[derive(Event)]
+pub struct CraftCompleted {
+    pub crafter: Entity,
+    pub recipe: RecipeId,
+}
+
 pub fn update_crafters(
     mut commands: Commands,
     time: Res<Time>,
-    mut q_crafters: Query<(
-        Entity,
-        &mut Crafter,
-        &mut Inventory,
-        Option<&mut LogisticRequest>,
-        &Transform,
-        &ChildOf,
-    )>,
+    mut q_crafters: Query<
+        (
+            Entity,
+            &mut Crafter,
+            &mut Inventory,
+            Option<&mut LogisticRequest>,
+            &Transform,
+            &ChildOf,
+            Option<&BuildingHealth>,
+        ),
+        Without<BuildingDisabled>,
+    >,
 ) {
-    for (entity, mut crafter, mut inventory, logistic_request, transform, child_of) in
+    for (entity, mut crafter, mut inventory, logistic_request, transform, child_of, health) in
         &mut q_crafters
     {
+        let efficiency = health.map_or(1., BuildingHealth::efficiency);
+
         // If a recipe is selected
         if let Some(recipe_crafter) = &mut crafter.recipe {
             // Try crafting
@@ -91,29 +108,37 @@ pub fn update_crafters(
                 CanCraftResult::Yes => {
                     commands.entity(entity).remove::<LogisticRequest>();
 
-                    if recipe_crafter.progress.tick(time.delta()).is_finished() {
+                    if recipe_crafter
+                        .progress
+                        .tick(time.delta().mul_f32(efficiency))
+                        .is_finished()
+                    {
                         recipe_crafter.progress.reset();
                         let building_output = inventory.craft(recipe_crafter.recipe);
+                        trigger_recipe_flows(&mut commands, entity, recipe_crafter.recipe);
+                        commands.trigger(CraftCompleted {
+                            crafter: entity,
+                            recipe: recipe_crafter.recipe,
+                        });
 
                         // SPAWN BUILDING if output is a building
-                        if let Some(building) = building_output.map(|b| b.data()) {
+                        if let Some(id) = building_output {
+                            let building = id.data();
                             debug!("Crafted building: {}", building.name);
 
                             if crafter.is_construction_site {
                                 commands.entity(entity).despawn();
                             }
 
+                            let mut built = Entity::PLACEHOLDER;
                             commands.entity(child_of.parent()).with_children(|c| {
-                                let mut ec = c.spawn((
-                                    BuildingHighlight,
-                                    SpriteLoader {
-                                        texture_path: building.sprite_path(),
-                                        ..default()
-                                    },
-                                    *transform,
-                                ));
-
-                                (building.on_build)(&mut ec);
+                                let mut ec = c.spawn_empty();
+                                building.build(&mut ec, *transform);
+                                built = ec.id();
+                            });
+                            commands.trigger(BuildingBuilt {
+                                building: built,
+                                id,
                             });
                         }
                     }
@@ -141,3 +166,27 @@ pub fn update_crafters(
         }
     }
 }
+
+fn trigger_recipe_flows(commands: &mut Commands, building: Entity, recipe: RecipeId) {
+    let recipe = recipe.data();
+
+    let outputs = match recipe.outputs() {
+        RecipeOutputs::Items(items) => items,
+        RecipeOutputs::Building(_) => &[],
+    };
+
+    let flows = recipe
+        .inputs()
+        .iter()
+        .map(|input| (FlowKind::Consumed, input))
+        .chain(outputs.iter().map(|output| (FlowKind::Produced, output)));
+
+    for (kind, (item, quantity)) in flows {
+        commands.trigger(ItemFlow {
+            building,
+            kind,
+            item: *item,
+            quantity: *quantity,
+        });
+    }
+}
@@ -2,8 +2,10 @@ use bevy::prelude::*;
 use rand::seq::IndexedRandom;
 
 use crate::{
+    buildings::{BuildingDisabled, BuildingHealth},
     data::{ELEMENTS, ItemId},
     items::{ElementState, Inventory, LogisticProvider, LogisticScope},
+    statistics::{FlowKind, ItemFlow},
     universe::Astre,
 };
 
@@ -56,12 +58,23 @@ impl Extractor {
 }
 
 pub fn update_extractors(
+    mut commands: Commands,
     time: Res<Time>,
-    mut q_extractors: Query<(&mut Extractor, &mut Inventory, &ChildOf), Without<Astre>>,
+    mut q_extractors: Query<
+        (
+            Entity,
+            &mut Extractor,
+            &mut Inventory,
+            &ChildOf,
+            Option<&BuildingHealth>,
+        ),
+        (Without<Astre>, Without<BuildingDisabled>),
+    >,
     mut q_astre_inventories: Query<&mut Inventory, With<Astre>>,
 ) {
-    for (mut extractor, mut extractor_inventory, child_of) in &mut q_extractors {
-        extractor.cooldown.tick(time.delta());
+    for (entity, mut extractor, mut extractor_inventory, child_of, health) in &mut q_extractors {
+        let efficiency = health.map_or(1., BuildingHealth::efficiency);
+        extractor.cooldown.tick(time.delta().mul_f32(efficiency));
 
         if extractor.cooldown.is_finished() && extractor_inventory.remaining_space() > 0 {
             let mut astre_inventory = q_astre_inventories.get_mut(child_of.parent()).unwrap();
@@ -76,7 +89,15 @@ pub fn update_extractors(
                         .quantity(*item_id)
                         .min(extractor.amount_per_tick);
 
-                    astre_inventory.transfer_to(&mut extractor_inventory, *item_id, quantity);
+                    let quantity =
+                        astre_inventory.transfer_to(&mut extractor_inventory, *item_id, quantity);
+
+                    commands.trigger(ItemFlow {
+                        building: entity,
+                        kind: FlowKind::Produced,
+                        item: *item_id,
+                        quantity,
+                    });
                 } else {
                     extractor.cached_item_ids = None;
                 }
@@ -1,7 +1,12 @@
 use bevy::{platform::collections::HashSet, prelude::*};
 
-use crate::items::{
-    Inventory, ItemMap, LogisticJourney, LogisticProvider, LogisticRequest, LogisticScope,
+use crate::{
+    buildings::{BuildingDisabled, BuildingHealth},
+    data::ItemId,
+    items::{
+        Inventory, ItemMap, LogisticJourney, LogisticProvider, LogisticRequest, LogisticScope,
+    },
+    statistics::{FlowKind, ItemFlow},
 };
 
 const RANGE: f32 = 100.0;
@@ -45,6 +50,11 @@ impl LogisticFreight {
         self.journey.as_ref().map(|(journey, _)| journey)
     }
 
+    // Provider or requester the freight is moving to
+    pub fn target(&self) -> Option<Entity> {
+        self.journey.as_ref().and_then(|(_, target)| *target)
+    }
+
     pub fn scope(&self) -> &LogisticScope {
         &self.scope
     }
@@ -77,6 +87,7 @@ pub fn update_logistic_freights(
             &ChildOf,
             &GlobalTransform,
             &Inventory,
+            Option<&BuildingHealth>,
         ),
         (Without<LogisticRequest>, Without<LogisticProvider>),
     >,
@@ -88,10 +99,19 @@ pub fn update_logistic_freights(
         &GlobalTransform,
         &Inventory,
     )>,
+    q_disabled: Query<(), With<BuildingDisabled>>,
     q_parent: Query<&ChildOf>,
 ) {
-    for (freight_entity, mut freight, child_of, transform, inventory) in &mut q_logistic_freights {
-        if freight.cooldown.tick(time.delta()).is_finished() {
+    for (freight_entity, mut freight, child_of, transform, inventory, health) in
+        &mut q_logistic_freights
+    {
+        let efficiency = health.map_or(1., BuildingHealth::efficiency);
+
+        if freight
+            .cooldown
+            .tick(time.delta().mul_f32(efficiency))
+            .is_finished()
+        {
             // If we already have a journey
             if let Some((journey, move_target)) = &mut freight.journey {
                 if let Ok((requester_entity, logistic_request, _, requester_transform)) =
@@ -117,7 +137,11 @@ pub fn update_logistic_freights(
                             }
                         } else {
                             // If freight inventory can't fullfill requester's request, go to provider
-                            if let Ok((provider_entity, _, _, provider_transform, _)) =
+                            if q_disabled.contains(journey.provider()) {
+                                // Disabled providers don't give items anymore, even to freights on their way
+                                debug!("Provider {:?} disabled", journey.provider());
+                                commands.trigger(UnregisterFreight(freight_entity));
+                            } else if let Ok((provider_entity, _, _, provider_transform, _)) =
                                 q_providers.get(journey.provider())
                             {
                                 *move_target = Some(provider_entity);
@@ -177,8 +201,7 @@ pub fn update_logistic_freights(
                     )
                     .collect::<Vec<_>>();
 
-                requesters
-                    .sort_by(|(_, a, ..), (_, b, ..)| a.freights.len().cmp(&b.freights.len()));
+                requesters.sort_by_key(|(_, logistic_request, ..)| logistic_request.freights.len());
 
                 for (requester_entity, logistic_request, ..) in requesters {
                     // Search for a compatible provider in the same scope,
@@ -196,7 +219,9 @@ pub fn update_logistic_freights(
                         provider_inventory,
                     ) in &q_providers
                     {
-                        if provider_entity == requester_entity {
+                        if provider_entity == requester_entity
+                            || q_disabled.contains(provider_entity)
+                        {
                             continue;
                         }
 
@@ -304,11 +329,76 @@ fn is_target_reached(a: &GlobalTransform, b: &GlobalTransform) -> bool {
     (a.translation().truncate() - b.translation().truncate()).length() < RANGE
 }
 
+// Journeys are not remapped when a save is loaded or a solar system rehydrated, and freights can be despawned:
+// drop the references to entities that don't exist anymore, freights will then look for a new journey
+pub fn repair_logistic_references(world: &mut World) {
+    let journeys = world
+        .query::<(Entity, &LogisticFreight)>()
+        .iter(world)
+        .filter_map(|(entity, freight)| {
+            freight
+                .journey
+                .map(|(journey, move_target)| (entity, journey, move_target))
+        })
+        .collect::<Vec<_>>();
+
+    let mut registered = HashSet::new();
+    let mut nb_repaired = 0;
+
+    for (freight_entity, journey, move_target) in journeys {
+        let valid = world
+            .get::<LogisticRequest>(journey.requester())
+            .is_some_and(|request| request.id() == journey.request_id())
+            && world
+                .get::<LogisticProvider>(journey.provider())
+                .is_some_and(|provider| provider.freights.contains(&freight_entity));
+
+        let move_target = move_target.filter(|target| world.get_entity(*target).is_ok());
+
+        let mut freight = world.get_mut::<LogisticFreight>(freight_entity).unwrap();
+
+        if valid {
+            registered.insert((freight_entity, journey.requester()));
+            registered.insert((freight_entity, journey.provider()));
+            freight.journey = Some((journey, move_target));
+        } else {
+            nb_repaired += 1;
+            freight.journey = None;
+        }
+    }
+
+    for (entity, mut logistic_request) in world
+        .query::<(Entity, &mut LogisticRequest)>()
+        .iter_mut(world)
+    {
+        let len = logistic_request.freights.len();
+        logistic_request
+            .freights
+            .retain(|freight| registered.contains(&(*freight, entity)));
+        nb_repaired += len - logistic_request.freights.len();
+    }
+
+    for (entity, mut logistic_provider) in world
+        .query::<(Entity, &mut LogisticProvider)>()
+        .iter_mut(world)
+    {
+        let len = logistic_provider.freights.len();
+        logistic_provider
+            .freights
+            .retain(|freight| registered.contains(&(*freight, entity)));
+        nb_repaired += len - logistic_provider.freights.len();
+    }
+
+    if nb_repaired > 0 {
+        info!("Dropped {nb_repaired} stale logistic references");
+    }
+}
+
 #[derive(Event)]
 pub struct RegisterFreight {
-    freight: Entity,
-    requester: Entity,
-    provider: Entity,
+    pub freight: Entity,
+    pub requester: Entity,
+    pub provider: Entity,
 }
 
 pub fn observe_register_freight(
@@ -348,7 +438,7 @@ pub fn observe_register_freight(
 }
 
 #[derive(Event)]
-pub struct UnregisterFreight(Entity);
+pub struct UnregisterFreight(pub Entity);
 
 pub fn observe_unregister_freight(
     unregister_freight: On<UnregisterFreight>,
@@ -382,6 +472,16 @@ pub struct FreightInventoryTransfer {
     is_provider: bool, // true = from provider to freight, false = from freight to requester
 }
 
+// Items actually moved by a FreightInventoryTransfer
+#[derive(Event)]
+pub struct FreightItemsTransferred {
+    pub freight: Entity,
+    pub provider_or_requester: Entity,
+    pub from_provider: bool,
+    pub item: ItemId,
+    pub quantity: u32,
+}
+
 pub fn observe_freight_inventory_transfer(
     freight_inv_transfer: On<FreightInventoryTransfer>,
     mut commands: Commands,
@@ -409,6 +509,23 @@ pub fn observe_freight_inventory_transfer(
 
         if q != 0 {
             debug!("Transferred {q} {item_id:?}");
+
+            commands.trigger(FreightItemsTransferred {
+                freight: freight_inv_transfer.freight,
+                provider_or_requester: freight_inv_transfer.provider_or_requester,
+                from_provider: freight_inv_transfer.is_provider,
+                item: item_id,
+                quantity: q,
+            });
+
+            if !freight_inv_transfer.is_provider {
+                commands.trigger(ItemFlow {
+                    building: freight_inv_transfer.provider_or_requester,
+                    kind: FlowKind::Delivered,
+                    item: item_id,
+                    quantity: q,
+                });
+            }
             return;
         }
     }
@@ -0,0 +1,151 @@
+use bevy::prelude::*;
+
+use crate::{
+    buildings::LogisticFreight,
+    data::ItemId,
+    items::{Inventory, LogisticProvider, LogisticRequest, LogisticScope},
+};
+
+const BADGE_SIZE: f32 = 30.;
+const BADGE_OFFSET: Vec2 = Vec2::new(0., 60.);
+const PLANET_SCOPE_COLOR: Color = Color::srgb(0.4, 0.9, 0.4);
+const SOLAR_SYSTEM_SCOPE_COLOR: Color = Color::srgb(0.9, 0.6, 0.2);
+const SELECTED_TARGET_COLOR: Color = Color::srgb(1.0, 1.0, 1.0);
+
+// Present while the logistics network is drawn
+#[derive(Resource, Debug)]
+pub struct LogisticOverlay;
+
+// Freight whose window is open, its current target being highlighted by the overlay
+#[derive(Component, Debug)]
+pub struct SelectedFreight(pub Entity);
+
+pub fn toggle_logistic_overlay(mut commands: Commands, overlay: Option<Res<LogisticOverlay>>) {
+    if overlay.is_some() {
+        commands.remove_resource::<LogisticOverlay>();
+    } else {
+        commands.insert_resource(LogisticOverlay);
+    }
+}
+
+fn scope_color(scope: LogisticScope) -> Color {
+    match scope {
+        LogisticScope::Planet => PLANET_SCOPE_COLOR,
+        LogisticScope::SolarSystem => SOLAR_SYSTEM_SCOPE_COLOR,
+    }
+}
+
+// Freights carry one item at a time: the first requested item the provider has
+fn transferred_item(
+    request: &LogisticRequest,
+    freight_inventory: &Inventory,
+    provider_inventory: &Inventory,
+) -> Option<ItemId> {
+    let mut requested = request.items().keys().copied();
+
+    requested
+        .clone()
+        .find(|id| freight_inventory.quantity(*id) > 0)
+        .or_else(|| requested.find(|id| provider_inventory.quantity(*id) > 0))
+}
+
+// The hue tells the item, planet journeys being lighter than solar system ones
+fn journey_color(item: Option<ItemId>, scope: LogisticScope) -> Color {
+    let index = item
+        .and_then(|item| ItemId::ALL.iter().position(|id| *id == item))
+        .unwrap_or_default();
+
+    let lightness = match scope {
+        LogisticScope::Planet => 0.75,
+        LogisticScope::SolarSystem => 0.5,
+    };
+
+    // Golden angle, so that neighbouring items get distinct hues
+    Color::hsl((index as f32 * 137.5) % 360., 0.8, lightness)
+}
+
+pub fn draw_logistic_overlay(
+    mut gizmos: Gizmos,
+    overlay: Option<Res<LogisticOverlay>>,
+    q_freights: Query<(&LogisticFreight, &Inventory, &GlobalTransform)>,
+    q_providers: Query<(&LogisticProvider, &GlobalTransform, &InheritedVisibility)>,
+    q_requesters: Query<(&LogisticRequest, &GlobalTransform, &InheritedVisibility)>,
+    q_inventories: Query<&Inventory>,
+    q_global_transforms: Query<&GlobalTransform>,
+    q_selected_freights: Query<&SelectedFreight>,
+) {
+    if overlay.is_none() {
+        return;
+    }
+
+    for (provider, transform, _) in q_providers.iter().filter(|(.., v)| v.get()) {
+        gizmos.rect_2d(
+            transform.translation().truncate() + BADGE_OFFSET,
+            Vec2::splat(BADGE_SIZE),
+            scope_color(*provider.scope()),
+        );
+    }
+
+    for (request, transform, _) in q_requesters.iter().filter(|(.., v)| v.get()) {
+        gizmos.circle_2d(
+            transform.translation().truncate() + BADGE_OFFSET,
+            BADGE_SIZE / 2.,
+            scope_color(*request.scope()),
+        );
+    }
+
+    for (freight, freight_inventory, _) in &q_freights {
+        let Some(journey) = freight.logistic_journey() else {
+            continue;
+        };
+
+        let (
+            Ok((_, provider_transform, provider_visibility)),
+            Ok(provider_inventory),
+            Ok((request, requester_transform, _)),
+        ) = (
+            q_providers.get(journey.provider()),
+            q_inventories.get(journey.provider()),
+            q_requesters.get(journey.requester()),
+        )
+        else {
+            continue;
+        };
+
+        // Only the solar system being played is drawn
+        if !provider_visibility.get() {
+            continue;
+        }
+
+        gizmos.arrow_2d(
+            provider_transform.translation().truncate(),
+            requester_transform.translation().truncate(),
+            journey_color(
+                transferred_item(request, freight_inventory, provider_inventory),
+                *freight.scope(),
+            ),
+        );
+    }
+
+    for selected_freight in &q_selected_freights {
+        let Ok((freight, _, freight_transform)) = q_freights.get(selected_freight.0) else {
+            continue;
+        };
+
+        let Some(target_transform) = freight
+            .target()
+            .and_then(|target| q_global_transforms.get(target).ok())
+        else {
+            continue;
+        };
+
+        let target_position = target_transform.translation().truncate();
+
+        gizmos.line_2d(
+            freight_transform.translation().truncate(),
+            target_position,
+            SELECTED_TARGET_COLOR,
+        );
+        gizmos.circle_2d(target_position, BADGE_SIZE * 2., SELECTED_TARGET_COLOR);
+    }
+}
@@ -1,40 +1,76 @@
-use bevy::prelude::*;
+use bevy::{input::common_conditions::input_just_pressed, prelude::*};
 
 use crate::SolarSystemSet;
 
 mod building;
+mod building_alert;
+mod building_condition;
+mod building_health;
+mod building_script;
 mod crafter;
 mod extractor;
 mod interstellar_gate;
 mod logistic_freight;
+mod logistic_overlay;
 mod spaceport;
+mod turret;
 mod warehouse;
 
 pub use building::*;
+pub use building_alert::*;
+pub use building_condition::*;
+pub use building_health::*;
+pub use building_script::*;
 pub use crafter::*;
 pub use extractor::*;
 pub use interstellar_gate::*;
 pub use logistic_freight::*;
+pub use logistic_overlay::*;
 pub use spaceport::*;
+pub use turret::*;
 pub use warehouse::*;
 
 pub struct BuildingsPlugin;
 
 impl Plugin for BuildingsPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
+        app.add_plugins(BuildingsLogicPlugin).add_systems(
             Update,
             (
                 spawn_building,
                 draw_placing_zones,
-                update_extractors,
+                toggle_logistic_overlay.run_if(input_just_pressed(KeyCode::KeyO)),
+                draw_logistic_overlay,
+                draw_turret_beams.after(update_turrets),
+                add_highlight_selection,
+            )
+                .in_set(SolarSystemSet),
+        );
+    }
+}
+
+// Extraction, crafting and logistics, without anything needing a window or rendering
+pub struct BuildingsLogicPlugin;
+
+impl Plugin for BuildingsLogicPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                update_building_conditions,
+                update_extractors.after(update_building_conditions),
                 update_logistic_freights,
                 update_logistic_freights_movement.after(update_logistic_freights),
-                update_crafters,
-                add_highlight_selection,
+                update_crafters.after(update_building_conditions),
+                destroy_buildings,
+                despawn_empty_wrecks,
+                run_building_scripts.after(update_building_conditions),
+                detect_stalled_buildings,
+                update_turrets,
             )
                 .in_set(SolarSystemSet),
         )
+        .init_resource::<ScriptEngine>()
         .add_observer(observe_unregister_freight)
         .add_observer(observe_freight_inventory_transfer)
         .add_observer(observe_register_freight);
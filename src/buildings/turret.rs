@@ -0,0 +1,150 @@
+use bevy::{platform::collections::HashMap, prelude::*};
+
+use crate::{
+    buildings::BuildingHealth,
+    data::ItemId,
+    items::{Inventory, LogisticRequest, LogisticScope},
+    universe::{Worm, WormSegment},
+};
+
+pub const TURRET_AMMO_CAPACITY: u32 = 500;
+const TURRET_AMMO_LOW: u32 = TURRET_AMMO_CAPACITY / 5; // request ammo under this quantity
+const TURRET_AMMO_FULL: u32 = TURRET_AMMO_CAPACITY * 9 / 10; // until this quantity is reached
+const TURRET_RANGE: f32 = 6000.;
+const TURRET_DAMAGE: f32 = 15.; // per shot
+const TURRET_FIRE_RATE: f32 = 0.5; // seconds between shots
+const TURRET_BEAM_COLOR: Color = Color::srgb(1.0, 0.3, 0.1);
+
+#[derive(Component, Reflect)]
+#[reflect(Component, Default)]
+#[require(Inventory)]
+pub struct Turret {
+    cooldown: Timer,
+    kills: u32,
+    #[reflect(ignore)]
+    target: Option<Entity>, // worm part aimed at, for the beam
+}
+
+impl Default for Turret {
+    fn default() -> Self {
+        Self {
+            cooldown: Timer::from_seconds(TURRET_FIRE_RATE, TimerMode::Once),
+            kills: 0,
+            target: None,
+        }
+    }
+}
+
+impl Turret {
+    pub fn kills(&self) -> u32 {
+        self.kills
+    }
+}
+
+// Fires at the closest worm in range, one ammo per shot, and requests ammo when running low
+// until the turret is refilled
+pub fn update_turrets(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut q_turrets: Query<(
+        Entity,
+        &mut Turret,
+        &mut Inventory,
+        &GlobalTransform,
+        Option<&LogisticRequest>,
+        Option<&BuildingHealth>,
+    )>,
+    q_worm_parts: Query<
+        (
+            Entity,
+            &GlobalTransform,
+            Option<&ChildOf>,
+            &InheritedVisibility,
+        ),
+        Or<(With<Worm>, With<WormSegment>)>,
+    >,
+    mut q_worms: Query<&mut Worm>,
+) {
+    for (entity, mut turret, mut inventory, global_transform, logistic_request, health) in
+        &mut q_turrets
+    {
+        let ammo = inventory.quantity(ItemId::TurretAmmo);
+
+        if ammo < TURRET_AMMO_LOW && logistic_request.is_none() {
+            commands.entity(entity).insert(LogisticRequest::new(
+                HashMap::from([(ItemId::TurretAmmo, TURRET_AMMO_CAPACITY - ammo)]),
+                LogisticScope::Planet,
+            ));
+        } else if ammo >= TURRET_AMMO_FULL && logistic_request.is_some() {
+            commands.entity(entity).remove::<LogisticRequest>();
+        }
+
+        let efficiency = health.map_or(1., BuildingHealth::efficiency);
+        turret.cooldown.tick(time.delta().mul_f32(efficiency));
+        turret.target = None;
+
+        if ammo == 0 {
+            continue;
+        }
+
+        let position = global_transform.translation().truncate();
+
+        let Some((part, part_child_of)) = q_worm_parts
+            .iter()
+            .filter(|(_, _, _, v)| v.get())
+            .map(|(e, g, child_of, _)| (e, g.translation().truncate(), child_of))
+            .filter(|(_, p, _)| p.distance(position) < TURRET_RANGE)
+            .min_by(|(_, a, _), (_, b, _)| a.distance(position).total_cmp(&b.distance(position)))
+            .map(|(e, _, child_of)| (e, child_of))
+        else {
+            continue;
+        };
+
+        turret.target = Some(part);
+
+        if !turret.cooldown.is_finished() {
+            continue;
+        }
+
+        turret.cooldown.reset();
+        inventory.try_remove(ItemId::TurretAmmo, 1);
+
+        let worm = if q_worms.contains(part) {
+            Some(part)
+        } else {
+            part_child_of.map(ChildOf::parent)
+        };
+
+        if let Some(mut worm) = worm.and_then(|worm| q_worms.get_mut(worm).ok())
+            && !worm.is_dead()
+        {
+            worm.damage(TURRET_DAMAGE);
+
+            if worm.is_dead() {
+                turret.kills += 1;
+            }
+        }
+    }
+}
+
+// Beam from each turret to the worm part it aims at
+pub fn draw_turret_beams(
+    mut gizmos: Gizmos,
+    q_turrets: Query<(&Turret, &GlobalTransform)>,
+    q_global_transforms: Query<&GlobalTransform>,
+) {
+    for (turret, global_transform) in &q_turrets {
+        let Some(target_transform) = turret
+            .target
+            .and_then(|target| q_global_transforms.get(target).ok())
+        else {
+            continue;
+        };
+
+        gizmos.line_2d(
+            global_transform.translation().truncate(),
+            target_transform.translation().truncate(),
+            TURRET_BEAM_COLOR,
+        );
+    }
+}
@@ -6,7 +6,9 @@ use crate::{
     buildings::*,
     enum_map,
     items::{Element, ElementState, Inventory, Item, Recipe},
-    universe::DockableOnAstre,
+    universe::{
+        DockableOnAstre, MiningLaserData, SHIP_ACTION_RANGE, ShipModuleData, ShipModuleEffect,
+    },
 };
 
 enum_map! {
@@ -62,6 +64,44 @@ enum_map! {
             name: "Plasma fuel",
             description: "High-energy spaceship fuel",
         },
+
+        // Ship modules
+        CargoExpander = Item {
+            name: "Cargo Expander",
+            description: "Ship module that increases the cargo capacity",
+        },
+        Thruster = Item {
+            name: "Thruster",
+            description: "Ship module that increases acceleration and top speed",
+        },
+        ImprovedMiningLaser = Item {
+            name: "Improved Mining Laser",
+            description: "Ship module that mines faster and further",
+        },
+        AdvancedMiningLaser = Item {
+            name: "Advanced Mining Laser",
+            description: "Ship module that mines much faster and further",
+        },
+        Scanner = Item {
+            name: "Scanner",
+            description: "Ship module that increases the range of ship actions",
+        },
+        ShieldGenerator = Item {
+            name: "Shield Generator",
+            description: "Ship module that protects the hull",
+        },
+
+        // Loot
+        WormScale = Item {
+            name: "Worm Scale",
+            description: "Tough scale dropped by killed worms",
+        },
+
+        // Ammunition
+        TurretAmmo = Item {
+            name: "Turret Ammo",
+            description: "Energy cell fired by turrets",
+        },
     }
 }
 
@@ -109,12 +149,55 @@ enum_map! {
             1.,
         ),
 
+        CraftTurretAmmo = Recipe::new_items(
+            &[(ItemId::Electronite, 1), (ItemId::Photonite, 1)],
+            &[(ItemId::TurretAmmo, 10)],
+            1.,
+        ),
+
         CraftComputingCore = Recipe::new_items(
             &[(ItemId::Electronite, 1), (ItemId::QuarkCrystal, 1)],
             &[(ItemId::ComputingCore, 1)],
             2.,
         ),
 
+        // Ship modules
+        CraftCargoExpander = Recipe::new_items(
+            &[(ItemId::Astrium, 20)],
+            &[(ItemId::CargoExpander, 1)],
+            5.,
+        ),
+
+        CraftThruster = Recipe::new_items(
+            &[(ItemId::Astrium, 10), (ItemId::PlasmaFuel, 10)],
+            &[(ItemId::Thruster, 1)],
+            5.,
+        ),
+
+        CraftImprovedMiningLaser = Recipe::new_items(
+            &[(ItemId::Electronite, 20), (ItemId::ComputingCore, 5)],
+            &[(ItemId::ImprovedMiningLaser, 1)],
+            5.,
+        ),
+
+        CraftAdvancedMiningLaser = Recipe::new_items(
+            &[(ItemId::ImprovedMiningLaser, 1), (ItemId::ComputingCore, 20), (ItemId::PlasmaFuel, 10)],
+            &[(ItemId::AdvancedMiningLaser, 1)],
+            10.,
+        ),
+
+        CraftScanner = Recipe::new_items(
+            &[(ItemId::ComputingCore, 5), (ItemId::QuarkCrystal, 10)],
+            &[(ItemId::Scanner, 1)],
+            5.,
+        ),
+
+        CraftShieldGenerator = Recipe::new_items(
+            &[(ItemId::Neutronite, 20), (ItemId::ComputingCore, 5)],
+            &[(ItemId::ShieldGenerator, 1)],
+            5.,
+        ),
+
         // Buildings
         Quarry = Recipe::new_building(
             &[],
@@ -181,6 +264,12 @@ enum_map! {
             BuildingId::InterstellarGate,
             1.,
         ),
+
+        Turret = Recipe::new_building(
+            &[(ItemId::Astrium, 10), (ItemId::Electronite, 10)],
+            BuildingId::Turret,
+            3.,
+        ),
     }
 }
 
@@ -266,6 +355,7 @@ enum_map! {
                 c.insert((Crafter::new_crafter(vec![
                     RecipeId::SmeltElectroniteOre,
                     RecipeId::CraftPlasmaFuel,
+                    RecipeId::CraftTurretAmmo,
                 ]), Inventory::new(100)));
             },
         },
@@ -278,6 +368,12 @@ enum_map! {
                 c.insert((Crafter::new_crafter(vec![
                     RecipeId::CraftComputingCore,
                     RecipeId::CargoShuttle,
+                    RecipeId::CraftCargoExpander,
+                    RecipeId::CraftThruster,
+                    RecipeId::CraftImprovedMiningLaser,
+                    RecipeId::CraftAdvancedMiningLaser,
+                    RecipeId::CraftScanner,
+                    RecipeId::CraftShieldGenerator,
                 ]), Inventory::new(100)));
             },
         },
@@ -290,5 +386,73 @@ enum_map! {
                 c.insert(InterstellarGate);
             },
         },
+
+        Turret = BuildingData {
+            name: "Turret",
+            sprite_name: "quarry",
+            location: LocationOnAstre::SurfaceOrCloseOrbit,
+            on_build: |c| {
+                c.insert((Turret::default(), Inventory::new(TURRET_AMMO_CAPACITY)));
+            },
+        },
+    }
+}
+
+enum_map! {
+    MiningLaserTier => MiningLaserData {
+        Basic = MiningLaserData {
+            name: "Basic mining laser",
+            amount_per_tick: 10,
+            range: SHIP_ACTION_RANGE,
+            cooldown: 0.5,
+        },
+
+        Improved = MiningLaserData {
+            name: "Improved mining laser",
+            amount_per_tick: 25,
+            range: SHIP_ACTION_RANGE * 1.5,
+            cooldown: 0.4,
+        },
+
+        Advanced = MiningLaserData {
+            name: "Advanced mining laser",
+            amount_per_tick: 60,
+            range: SHIP_ACTION_RANGE * 2.5,
+            cooldown: 0.3,
+        },
+    }
+}
+
+enum_map! {
+    ShipModuleId => ShipModuleData {
+        CargoExpander = ShipModuleData {
+            item: ItemId::CargoExpander,
+            effect: ShipModuleEffect::Cargo(50_000),
+        },
+
+        Thruster = ShipModuleData {
+            item: ItemId::Thruster,
+            effect: ShipModuleEffect::Thruster { thrust: 1500., max_speed: 25. },
+        },
+
+        ImprovedMiningLaser = ShipModuleData {
+            item: ItemId::ImprovedMiningLaser,
+            effect: ShipModuleEffect::MiningLaser(MiningLaserTier::Improved),
+        },
+
+        AdvancedMiningLaser = ShipModuleData {
+            item: ItemId::AdvancedMiningLaser,
+            effect: ShipModuleEffect::MiningLaser(MiningLaserTier::Advanced),
+        },
+
+        Scanner = ShipModuleData {
+            item: ItemId::Scanner,
+            effect: ShipModuleEffect::Scanner(SHIP_ACTION_RANGE),
+        },
+
+        ShieldGenerator = ShipModuleData {
+            item: ItemId::ShieldGenerator,
+            effect: ShipModuleEffect::Shield(100.),
+        },
     }
 }
@@ -4,7 +4,7 @@ macro_rules! enum_map {
         $first_name:ident = $first_data:expr,
         $( $name:ident = $data:expr ),* $(,)?
     }) => {
-        #[derive(PartialEq, Eq, Hash, Clone, Copy, Reflect, Default, Debug)]
+        #[derive(PartialEq, Eq, Hash, Clone, Copy, Reflect, Default, Debug, serde::Serialize, serde::Deserialize)]
         pub enum $enum_name {
             #[default]
             $first_name,
@@ -0,0 +1,72 @@
+use std::{path::Path, time::Duration};
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+
+use crate::{
+    Scenario, ScenarioError, buildings::BuildingsLogicPlugin, check_scenario_goals, data::ItemId,
+    items::Inventory, journal::JournalPlugin, statistics::StatisticsPlugin, universe::Astre,
+};
+
+// Simulated time between two updates, independent of the real time it takes to run them
+pub const HEADLESS_TICK: Duration = Duration::from_millis(100);
+
+// Number of updates simulating a given duration
+pub const ONE_SECOND: u32 = (Duration::from_secs(1).as_millis() / HEADLESS_TICK.as_millis()) as u32;
+pub const ONE_MINUTE: u32 = 60 * ONE_SECOND;
+
+// Runs the economy (extractors, crafters and logistics) without a window nor rendering,
+// so that factories can be simulated in tests
+pub struct HeadlessPlugin;
+
+impl Plugin for HeadlessPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((
+            MinimalPlugins,
+            TransformPlugin,
+            BuildingsLogicPlugin,
+            StatisticsPlugin,
+            JournalPlugin,
+        ))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(HEADLESS_TICK))
+        .add_systems(Update, check_scenario_goals);
+    }
+}
+
+pub fn headless_app() -> App {
+    let mut app = App::new();
+    app.add_plugins(HeadlessPlugin);
+    app
+}
+
+// Universe of a scenario file, ready to be simulated
+pub fn headless_scenario_app(path: &Path) -> Result<App, ScenarioError> {
+    let scenario = Scenario::from_file(path)?;
+    let mut app = headless_app();
+    scenario.spawn(app.world_mut())?;
+    Ok(app)
+}
+
+pub fn run_ticks(app: &mut App, ticks: u32) {
+    for _ in 0..ticks {
+        app.update();
+    }
+}
+
+// Astre whose inventory holds the items extractors can mine
+pub fn spawn_astre(world: &mut World, surface_radius: f32, inventory: Inventory) -> Entity {
+    world
+        .spawn((
+            Name::new("Astre"),
+            Astre::new(surface_radius, 0., surface_radius),
+            inventory,
+            Transform::default(),
+        ))
+        .id()
+}
+
+// Planet with the given quantity of ore for quarries to extract
+pub fn spawn_ore_planet(world: &mut World, ore: u32) -> Entity {
+    let mut inventory = Inventory::new(0);
+    inventory.try_add(ItemId::ElectroniteOre, ore);
+    spawn_astre(world, 5000., inventory)
+}
@@ -5,7 +5,7 @@ use crate::{
     items::{ElementOnAstre, ItemMap, RecipeOutputs},
 };
 
-#[derive(Component, Reflect, Default, Debug)]
+#[derive(Component, Reflect, Default, Debug, Clone, PartialEq)]
 #[reflect(Component, Default)]
 pub struct Inventory {
     items: ItemMap,
@@ -38,6 +38,34 @@ impl Inventory {
     }
 
 
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+
+    pub fn set_size(&mut self, size: u32) {
+        self.size = size;
+    }
+
+    // Removes the item only if the inventory has enough of it
+    pub fn try_remove(&mut self, id: ItemId, quantity: u32) -> bool {
+        if self.quantity(id) < quantity {
+            return false;
+        }
+
+        self.remove(id, quantity);
+        true
+    }
+
+    // Adds the item only if the inventory has enough space for it
+    pub fn try_add(&mut self, id: ItemId, quantity: u32) -> bool {
+        if self.size != 0 && self.remaining_space() < quantity {
+            return false;
+        }
+
+        self.add(id, quantity);
+        true
+    }
+
     pub fn remaining_space(&self) -> u32 {
         self.size.saturating_sub(
             self.items
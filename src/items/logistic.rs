@@ -2,6 +2,7 @@ use bevy::{
     ecs::{entity::MapEntities, reflect::ReflectMapEntities},
     prelude::*,
 };
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::{
@@ -9,7 +10,7 @@ use crate::{
     items::{Inventory, ItemMap},
 };
 
-#[derive(PartialEq, Eq, Clone, Copy, Reflect, Default, Debug)]
+#[derive(PartialEq, Eq, Clone, Copy, Reflect, Default, Debug, Serialize, Deserialize)]
 pub enum LogisticScope {
     #[default]
     Planet,
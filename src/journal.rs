@@ -0,0 +1,204 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{LineWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use bevy::prelude::*;
+use serde::Serialize;
+
+use crate::{
+    UniverseClock,
+    buildings::{
+        BuildingBuilt, CraftCompleted, FreightItemsTransferred, RegisterFreight, UnregisterFreight,
+    },
+    data::{BuildingId, ItemId, RecipeId},
+    universe::{ShipMined, TravelToSolarSystem},
+};
+
+pub const JOURNALS_DIR: &str = "journals";
+
+// One line of the journal
+#[derive(Serialize, Debug)]
+#[serde(tag = "event")]
+pub enum JournalEntry {
+    CraftCompleted {
+        crafter: Entity,
+        recipe: RecipeId,
+    },
+    BuildingBuilt {
+        building: Entity,
+        id: BuildingId,
+    },
+    FreightRegistered {
+        freight: Entity,
+        requester: Entity,
+        provider: Entity,
+    },
+    FreightUnregistered {
+        freight: Entity,
+    },
+    FreightTransfer {
+        freight: Entity,
+        provider_or_requester: Entity,
+        from_provider: bool,
+        item: ItemId,
+        quantity: u32,
+    },
+    ShipMined {
+        ship: Entity,
+        astre: Entity,
+        item: ItemId,
+        quantity: u32,
+    },
+    Travel {
+        position: [i32; 2],
+    },
+}
+
+#[derive(Serialize)]
+struct JournalLine<'a> {
+    game_time: f64, // seconds
+    #[serde(flatten)]
+    entry: &'a JournalEntry,
+}
+
+// Appends the game events to a JSON Lines file while started
+#[derive(Resource, Default)]
+pub struct EventJournal {
+    file: Option<(PathBuf, LineWriter<File>)>,
+}
+
+impl EventJournal {
+    pub fn start(&mut self, path: &Path) -> std::io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        self.file = Some((path.to_path_buf(), LineWriter::new(file)));
+
+        info!("Journal started in {}", path.display());
+        Ok(())
+    }
+
+    pub fn stop(&mut self) {
+        if let Some((path, _)) = self.file.take() {
+            info!("Journal stopped in {}", path.display());
+        }
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.file.as_ref().map(|(path, _)| path.as_path())
+    }
+
+    pub fn is_started(&self) -> bool {
+        self.file.is_some()
+    }
+
+    // Stops the journal if the file can't be written anymore
+    pub fn write(&mut self, game_time: f64, entry: &JournalEntry) {
+        let Some((path, writer)) = &mut self.file else {
+            return;
+        };
+
+        let result = serde_json::to_writer(&mut *writer, &JournalLine { game_time, entry })
+            .map_err(std::io::Error::from)
+            .and_then(|()| writeln!(writer));
+
+        if let Err(e) = result {
+            error!("Can't write journal {}: {e}", path.display());
+            self.stop();
+        }
+    }
+}
+
+// Journal in assets/journals, named after the universe
+pub fn journal_path(universe_name: &str) -> PathBuf {
+    PathBuf::from(format!("assets/{JOURNALS_DIR}/{universe_name}.jsonl"))
+}
+
+// `--journal <path>` command line argument
+pub fn start_journal_from_command_line(mut journal: ResMut<EventJournal>) {
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        if arg == "--journal" {
+            let Some(path) = args.next() else {
+                error!("--journal expects a file path");
+                continue;
+            };
+
+            if let Err(e) = journal.start(&PathBuf::from(&path)) {
+                error!("Can't start journal {path}: {e}");
+            }
+        }
+    }
+}
+
+// Outside of a universe (e.g. headless tests), the time since startup is used
+fn game_time(clock: Option<Res<UniverseClock>>, time: Res<Time>) -> f64 {
+    clock.map_or(time.elapsed_secs_f64(), |clock| clock.game_time)
+}
+
+fn journal_observer<E: Event>(
+    to_entry: impl Fn(&E) -> JournalEntry + Send + Sync + 'static,
+) -> impl Fn(On<E>, ResMut<EventJournal>, Option<Res<UniverseClock>>, Res<Time>) {
+    move |event, mut journal, clock, time| {
+        if journal.is_started() {
+            journal.write(game_time(clock, time), &to_entry(&event));
+        }
+    }
+}
+
+pub struct JournalPlugin;
+
+impl Plugin for JournalPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<EventJournal>()
+            .add_observer(journal_observer(|craft: &CraftCompleted| {
+                JournalEntry::CraftCompleted {
+                    crafter: craft.crafter,
+                    recipe: craft.recipe,
+                }
+            }))
+            .add_observer(journal_observer(|built: &BuildingBuilt| {
+                JournalEntry::BuildingBuilt {
+                    building: built.building,
+                    id: built.id,
+                }
+            }))
+            .add_observer(journal_observer(|register: &RegisterFreight| {
+                JournalEntry::FreightRegistered {
+                    freight: register.freight,
+                    requester: register.requester,
+                    provider: register.provider,
+                }
+            }))
+            .add_observer(journal_observer(|unregister: &UnregisterFreight| {
+                JournalEntry::FreightUnregistered {
+                    freight: unregister.0,
+                }
+            }))
+            .add_observer(journal_observer(|transfer: &FreightItemsTransferred| {
+                JournalEntry::FreightTransfer {
+                    freight: transfer.freight,
+                    provider_or_requester: transfer.provider_or_requester,
+                    from_provider: transfer.from_provider,
+                    item: transfer.item,
+                    quantity: transfer.quantity,
+                }
+            }))
+            .add_observer(journal_observer(|mined: &ShipMined| {
+                JournalEntry::ShipMined {
+                    ship: mined.ship,
+                    astre: mined.astre,
+                    item: mined.item,
+                    quantity: mined.quantity,
+                }
+            }))
+            .add_observer(journal_observer(|travel: &TravelToSolarSystem| {
+                JournalEntry::Travel { position: travel.0 }
+            }));
+    }
+}
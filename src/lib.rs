@@ -0,0 +1,30 @@
+// Public functions are mostly used by the game itself, not documented as a library API
+#![allow(
+    clippy::must_use_candidate,
+    clippy::return_self_not_must_use,
+    clippy::missing_errors_doc,
+    clippy::missing_panics_doc
+)]
+
+pub mod buildings;
+pub mod data;
+mod enum_map;
+pub mod handle_loader;
+pub mod headless;
+pub mod items;
+pub mod journal;
+pub mod main_menu;
+pub mod remote;
+pub mod save_load;
+pub mod scenario;
+pub mod state;
+pub mod statistics;
+pub mod ui;
+pub mod universe;
+
+pub use handle_loader::*;
+pub use main_menu::*;
+pub use remote::*;
+pub use save_load::*;
+pub use scenario::*;
+pub use state::*;
@@ -1,22 +1,12 @@
+use astras::{
+    buildings::BuildingsPlugin,
+    journal::{JournalPlugin, start_journal_from_command_line},
+    statistics::{StatisticsPlugin, reset_statistics},
+    ui::UIPlugin,
+    universe::UniversePlugin,
+    *,
+};
 use bevy::{input::common_conditions::input_just_pressed, prelude::*};
-use buildings::BuildingsPlugin;
-use handle_loader::*;
-use main_menu::*;
-use save_load::*;
-use state::*;
-use ui::UIPlugin;
-use universe::UniversePlugin;
-
-mod buildings;
-mod data;
-mod enum_map;
-mod handle_loader;
-mod items;
-mod main_menu;
-mod save_load;
-mod state;
-mod ui;
-mod universe;
 
 fn main() {
     App::new()
@@ -26,12 +16,18 @@ fn main() {
             // bevy::dev_tools::picking_debug::DebugPickingPlugin,
             // bevy::diagnostic::LogDiagnosticsPlugin::default(),
             // bevy::diagnostic::FrameTimeDiagnosticsPlugin
-            bevy::remote::RemotePlugin::default(),
+            with_astras_methods(bevy::remote::RemotePlugin::default()),
             bevy::remote::http::RemoteHttpPlugin::default()
                 .with_header("Access-Control-Allow-Origin", "*"),
         ))
         // .insert_resource(bevy::dev_tools::picking_debug::DebugPickingMode::Normal)
-        .add_plugins((UniversePlugin, UIPlugin, BuildingsPlugin))
+        .add_plugins((
+            UniversePlugin,
+            UIPlugin,
+            BuildingsPlugin,
+            StatisticsPlugin,
+            JournalPlugin,
+        ))
         .insert_resource(ClearColor(Color::BLACK))
         .configure_sets(
             PreUpdate,
@@ -52,13 +48,25 @@ fn main() {
             PostUpdate,
             (SolarSystemSet.run_if(in_state(GameState::GameSolarSystem)),),
         )
-        .add_systems(OnEnter(GameState::MainMenu), setup_main_menu)
+        .add_systems(
+            OnEnter(GameState::MainMenu),
+            (setup_main_menu, reset_statistics),
+        )
+        .add_systems(
+            Startup,
+            (load_from_command_line, start_journal_from_command_line),
+        )
+        .add_systems(Update, poll_save_tasks)
+        .init_resource::<Autosave>()
         .add_systems(
             Update,
             ((
                 scan_sprite_loaders,
+                update_universe_clock,
+                check_scenario_goals,
+                autosave,
                 (|mut commands: Commands| {
-                    commands.queue(SaveUniverse);
+                    commands.queue(SaveUniverse::default());
                 })
                 .run_if(input_just_pressed(KeyCode::KeyL)),
             )
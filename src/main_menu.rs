@@ -2,9 +2,10 @@ use bevy::prelude::*;
 use rand::Rng;
 
 use crate::{
-    GameState, UniverseName,
-    ui::{UiButton, build_load_ui},
-    universe::{build_ship, build_solar_system},
+    Autosave, GameState, SCENARIO_EXTENSION, SCENARIOS_DIR, Scenario, StartScenario, UniverseClock,
+    UniverseName, UniverseSeed,
+    ui::{NotificationZone, UiButton, build_load_ui},
+    universe::{SolarSystem, assign_procedural_ids, build_ship, build_solar_system},
 };
 
 pub fn setup_main_menu(mut commands: Commands) {
@@ -29,8 +30,59 @@ pub fn setup_main_menu(mut commands: Commands) {
                     parent.spawn(Text::new("New game"));
                 });
 
+            build_scenario_list(c);
+
             build_load_ui(c);
         });
+
+    // Load errors are shown here
+    commands.spawn((
+        DespawnOnExit(GameState::MainMenu),
+        NotificationZone,
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(5.0),
+            right: Val::Px(5.0),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::FlexEnd,
+            ..default()
+        },
+        Pickable::IGNORE,
+    ));
+}
+
+// One button per scenario file
+fn build_scenario_list(c: &mut ChildSpawnerCommands) {
+    let Ok(dir) = std::fs::read_dir(format!("assets/{SCENARIOS_DIR}")) else {
+        return;
+    };
+
+    let mut scenarios = dir
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let file_name = path.file_name()?.to_str()?;
+            let name = file_name.strip_suffix(&format!(".{SCENARIO_EXTENSION}"))?;
+
+            // Invalid scenarios are still listed, the error is shown when starting them
+            let name = Scenario::from_file(&path)
+                .map(|scenario| scenario.name)
+                .unwrap_or(name.to_string());
+
+            Some((name, path))
+        })
+        .collect::<Vec<_>>();
+
+    scenarios.sort();
+
+    for (name, path) in scenarios {
+        c.spawn(UiButton)
+            .with_child(Text::new(format!("Scenario: {name}")))
+            .observe(
+                move |_pointer_click: On<Pointer<Click>>, mut commands: Commands| {
+                    commands.queue(StartScenario(path.clone()));
+                },
+            );
+    }
 }
 
 fn spawn_new_game(
@@ -45,9 +97,18 @@ fn spawn_new_game(
 
     let timestamp = std::time::UNIX_EPOCH.elapsed().unwrap().as_millis();
     commands.insert_resource(UniverseName(format!("universe_{timestamp}")));
+    commands.insert_resource(UniverseSeed(
+        SolarSystem {
+            position: solar_system_position,
+        }
+        .seed(),
+    ));
+    commands.insert_resource(UniverseClock::default());
+    commands.insert_resource(Autosave::default());
 
     commands
         .spawn((build_solar_system(solar_system_position),))
+        .queue(assign_procedural_ids)
         .with_child(build_ship());
 
     next_state.set(GameState::GameSolarSystem);
@@ -0,0 +1,257 @@
+use std::collections::HashMap;
+
+use bevy::{
+    prelude::*,
+    remote::{BrpError, BrpResult, RemotePlugin, error_codes},
+};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::Value;
+
+use crate::{
+    GameState, SaveUniverse, UniverseName,
+    buildings::{BUILDING_SCALE, Crafter, build_construction_site, construction_recipe},
+    data::{BuildingId, ItemId, RecipeId},
+    items::{Inventory, ItemMap, LogisticRequest, LogisticScope},
+    universe::{Astre, TravelToSolarSystem},
+};
+
+// As buildings docked on an astre
+const CONSTRUCTION_SITE_Z: f32 = 0.5;
+
+// Game-level methods of the Bevy Remote Protocol, on top of the generic ECS ones
+pub fn with_astras_methods(remote_plugin: RemotePlugin) -> RemotePlugin {
+    remote_plugin
+        .with_method("astras/inventory", process_inventory_request)
+        .with_method("astras/place_building", process_place_building_request)
+        .with_method("astras/set_recipe", process_set_recipe_request)
+        .with_method(
+            "astras/set_logistic_request",
+            process_set_logistic_request_request,
+        )
+        .with_method("astras/save", process_save_request)
+        .with_method("astras/travel", process_travel_request)
+}
+
+#[derive(Deserialize)]
+pub struct InventoryParams {
+    pub entity: Entity,
+}
+
+#[derive(Serialize)]
+pub struct InventoryResponse {
+    pub size: u32, // 0 = infinite
+    pub items: HashMap<ItemId, u32>,
+}
+
+#[derive(Deserialize)]
+pub struct PlaceBuildingParams {
+    pub astre: Entity,
+    pub building: BuildingId,
+    pub position: [f32; 2], // relative to the astre
+}
+
+#[derive(Serialize)]
+pub struct PlaceBuildingResponse {
+    pub entity: Entity,
+}
+
+#[derive(Deserialize)]
+pub struct SetRecipeParams {
+    pub entity: Entity,
+    pub recipe: RecipeId,
+}
+
+// An empty request removes the current one
+#[derive(Deserialize)]
+pub struct SetLogisticRequestParams {
+    pub entity: Entity,
+    #[serde(default)]
+    pub scope: LogisticScope,
+    pub items: HashMap<ItemId, u32>,
+}
+
+#[derive(Serialize)]
+pub struct SaveResponse {
+    pub universe_name: String,
+}
+
+#[derive(Deserialize)]
+pub struct TravelParams {
+    pub position: [i32; 2],
+}
+
+fn parse_params<T: DeserializeOwned>(params: Option<Value>) -> Result<T, BrpError> {
+    let Some(params) = params else {
+        return Err(invalid_params("Params not provided"));
+    };
+
+    serde_json::from_value(params).map_err(|e| invalid_params(&e.to_string()))
+}
+
+fn invalid_params(message: &str) -> BrpError {
+    BrpError {
+        code: error_codes::INVALID_PARAMS,
+        message: message.to_string(),
+        data: None,
+    }
+}
+
+fn ensure_in_game(world: &World) -> Result<(), BrpError> {
+    match world.get_resource::<State<GameState>>().map(State::get) {
+        Some(GameState::GameSolarSystem | GameState::GameUniverseMap) => Ok(()),
+        _ => Err(BrpError::internal("no universe is being played")),
+    }
+}
+
+fn to_value<T: Serialize>(response: &T) -> BrpResult {
+    serde_json::to_value(response).map_err(BrpError::internal)
+}
+
+pub fn process_inventory_request(In(params): In<Option<Value>>, world: &mut World) -> BrpResult {
+    let InventoryParams { entity } = parse_params(params)?;
+
+    let entity_ref = world
+        .get_entity(entity)
+        .map_err(|_| BrpError::entity_not_found(entity))?;
+
+    let inventory = entity_ref
+        .get::<Inventory>()
+        .ok_or_else(|| BrpError::component_not_present("Inventory", entity))?;
+
+    to_value(&InventoryResponse {
+        size: inventory.size(),
+        items: inventory
+            .items()
+            .iter()
+            .map(|(id, quantity)| (*id, *quantity))
+            .collect(),
+    })
+}
+
+// Spawns a construction site, where the building could be placed in game
+pub fn process_place_building_request(
+    In(params): In<Option<Value>>,
+    world: &mut World,
+) -> BrpResult {
+    let PlaceBuildingParams {
+        astre,
+        building,
+        position,
+    } = parse_params(params)?;
+
+    let astre_ref = world
+        .get_entity(astre)
+        .map_err(|_| BrpError::entity_not_found(astre))?;
+
+    let astre_data = astre_ref
+        .get::<Astre>()
+        .ok_or_else(|| BrpError::component_not_present("Astre", astre))?;
+
+    let position = Vec2::from(position);
+
+    if !building
+        .data()
+        .location
+        .allows(astre_data, position.length())
+    {
+        return Err(invalid_params(&format!(
+            "{building:?} can't be built at {position} on {astre}"
+        )));
+    }
+
+    let recipe = construction_recipe(building)
+        .ok_or_else(|| invalid_params(&format!("{building:?} can't be built")))?;
+
+    let entity = world
+        .spawn((
+            build_construction_site(
+                building,
+                recipe,
+                Transform::from_translation(position.extend(CONSTRUCTION_SITE_Z))
+                    .with_scale(Vec3::splat(BUILDING_SCALE)),
+            ),
+            ChildOf(astre),
+        ))
+        .id();
+
+    to_value(&PlaceBuildingResponse { entity })
+}
+
+pub fn process_set_recipe_request(In(params): In<Option<Value>>, world: &mut World) -> BrpResult {
+    let SetRecipeParams { entity, recipe } = parse_params(params)?;
+
+    let mut entity_mut = world
+        .get_entity_mut(entity)
+        .map_err(|_| BrpError::entity_not_found(entity))?;
+
+    let mut crafter = entity_mut
+        .get_mut::<Crafter>()
+        .ok_or_else(|| BrpError::component_not_present("Crafter", entity))?;
+
+    if crafter.is_construction_site() || !crafter.possible_recipes().contains(&recipe) {
+        return Err(invalid_params(&format!(
+            "{recipe:?} can't be crafted by {entity}"
+        )));
+    }
+
+    crafter.set_recipe(recipe);
+
+    Ok(Value::Null)
+}
+
+pub fn process_set_logistic_request_request(
+    In(params): In<Option<Value>>,
+    world: &mut World,
+) -> BrpResult {
+    let SetLogisticRequestParams {
+        entity,
+        scope,
+        items,
+    } = parse_params(params)?;
+
+    let mut entity_mut = world
+        .get_entity_mut(entity)
+        .map_err(|_| BrpError::entity_not_found(entity))?;
+
+    if !entity_mut.contains::<Inventory>() {
+        return Err(BrpError::component_not_present("Inventory", entity));
+    }
+
+    let items = items
+        .into_iter()
+        .filter(|(_, quantity)| *quantity > 0)
+        .collect::<ItemMap>();
+
+    if items.is_empty() {
+        entity_mut.remove::<LogisticRequest>();
+    } else {
+        entity_mut.insert(LogisticRequest::new(items, scope));
+    }
+
+    Ok(Value::Null)
+}
+
+// The save is written in the background, like the ones made from the game
+pub fn process_save_request(In(_params): In<Option<Value>>, world: &mut World) -> BrpResult {
+    ensure_in_game(world)?;
+
+    let universe_name = world
+        .get_resource::<UniverseName>()
+        .ok_or_else(|| BrpError::resource_not_present("UniverseName"))?
+        .0
+        .clone();
+
+    SaveUniverse::default().apply(world);
+
+    to_value(&SaveResponse { universe_name })
+}
+
+pub fn process_travel_request(In(params): In<Option<Value>>, world: &mut World) -> BrpResult {
+    let TravelParams { position } = parse_params(params)?;
+
+    ensure_in_game(world)?;
+
+    world.trigger(TravelToSolarSystem(position));
+
+    Ok(Value::Null)
+}
@@ -0,0 +1,218 @@
+use std::{
+    fmt,
+    fs::File,
+    io::{BufRead, BufReader},
+    path::Path,
+};
+
+use bevy::{
+    reflect::TypeRegistry,
+    scene::{DynamicScene, ron, serde::SceneDeserializer},
+};
+use serde::{Deserialize, Serialize, de::DeserializeSeed};
+
+use super::migrations::*;
+
+// Bump when a change breaks older saves (renamed component field, ItemId variant, ...)
+// and add the matching migration at the end of MIGRATIONS
+pub const SAVE_FORMAT_VERSION: u32 = 2;
+
+// MIGRATIONS[n] rewrites the scene of a save from version n to version n + 1
+const MIGRATIONS: &[fn(String) -> String] = &[
+    // 0 -> 1: saves were raw DynamicScenes without a header, the scene itself is unchanged
+    |scene| scene,
+    // 1 -> 2: mining laser beams aren't entities anymore, dormant solar systems keep worms and count buildings
+    |scene| {
+        complete_dormant_solar_systems(remove_entities_with(
+            scene,
+            "astras::universe::laser::Laser",
+        ))
+    },
+];
+
+// First line of a save file, readable without deserializing the scene
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SaveHeader {
+    pub version: u32,
+    #[serde(default)]
+    pub metadata: SaveMetadata,
+}
+
+impl Default for SaveHeader {
+    fn default() -> Self {
+        Self {
+            version: SAVE_FORMAT_VERSION,
+            metadata: SaveMetadata::default(),
+        }
+    }
+}
+
+// Shown in the load menu. Missing fields (older saves) are defaulted.
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[serde(default)]
+pub struct SaveMetadata {
+    pub universe_name: String,
+    pub seed: u64,
+    pub timestamp: u64, // seconds since UNIX epoch
+    pub game_time: f64, // seconds
+    pub play_time: f64, // seconds
+    pub visited_systems: usize,
+    pub buildings: usize,
+}
+
+#[derive(Debug)]
+pub enum SaveError {
+    Io(std::io::Error),
+    UnsupportedVersion(u32),
+    Scene(String),
+    InvalidName(String),
+    AlreadyExists(String),
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaveError::Io(e) => write!(f, "{e}"),
+            SaveError::UnsupportedVersion(version) => write!(
+                f,
+                "save format version {version} is newer than the supported version {SAVE_FORMAT_VERSION}"
+            ),
+            SaveError::Scene(e) => write!(f, "invalid scene: {e}"),
+            SaveError::InvalidName(name) => write!(f, "invalid universe name \"{name}\""),
+            SaveError::AlreadyExists(name) => write!(f, "universe {name} already exists"),
+        }
+    }
+}
+
+impl From<std::io::Error> for SaveError {
+    fn from(e: std::io::Error) -> Self {
+        SaveError::Io(e)
+    }
+}
+
+pub fn write_save(header: &SaveHeader, scene: &str) -> Result<String, SaveError> {
+    let header = ron::to_string(header).map_err(|e| SaveError::Scene(e.to_string()))?;
+    Ok(format!("{header}\n{scene}"))
+}
+
+// Saves without a header line are version 0
+pub fn split_save(content: &str) -> (SaveHeader, &str) {
+    content
+        .split_once('\n')
+        .and_then(|(first_line, scene)| {
+            ron::from_str::<SaveHeader>(first_line)
+                .ok()
+                .map(|header| (header, scene))
+        })
+        .unwrap_or((
+            SaveHeader {
+                version: 0,
+                metadata: SaveMetadata::default(),
+            },
+            content,
+        ))
+}
+
+// Only reads the first line of the file
+pub fn read_save_header(path: &Path) -> Result<SaveHeader, SaveError> {
+    let mut first_line = String::new();
+    BufReader::new(File::open(path)?).read_line(&mut first_line)?;
+
+    Ok(split_save(&first_line).0)
+}
+
+pub fn migrate_scene(version: u32, scene: String) -> Result<String, SaveError> {
+    if version > SAVE_FORMAT_VERSION {
+        return Err(SaveError::UnsupportedVersion(version));
+    }
+
+    Ok(MIGRATIONS[version as usize..]
+        .iter()
+        .fold(scene, |scene, migration| migration(scene)))
+}
+
+pub fn read_save(
+    content: &str,
+    type_registry: &TypeRegistry,
+) -> Result<(SaveHeader, DynamicScene), SaveError> {
+    let (header, scene) = split_save(content);
+
+    let scene = migrate_scene(header.version, scene.to_string())?;
+
+    let mut deserializer =
+        ron::de::Deserializer::from_str(&scene).map_err(|e| SaveError::Scene(e.to_string()))?;
+
+    let scene = SceneDeserializer { type_registry }
+        .deserialize(&mut deserializer)
+        .map_err(|e| SaveError::Scene(deserializer.span_error(e).to_string()))?;
+
+    Ok((header, scene))
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{ecs::entity::EntityHashMap, prelude::*};
+
+    use super::*;
+    use crate::universe::{Ship, SolarSystem};
+
+    const FIXTURES_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/saves");
+
+    // Every save format version must keep a fixture, and every fixture must keep loading
+    #[test]
+    fn fixture_saves_load() {
+        let type_registry = AppTypeRegistry::new_with_derived_types();
+
+        let mut versions = vec![];
+
+        for entry in std::fs::read_dir(FIXTURES_DIR).unwrap() {
+            let path = entry.unwrap().path();
+            let content = std::fs::read_to_string(&path).unwrap();
+
+            versions.push(split_save(&content).0.version);
+
+            let (_, scene) = read_save(&content, &type_registry.read())
+                .unwrap_or_else(|e| panic!("{}: {e}", path.display()));
+
+            let mut world = World::new();
+            world.insert_resource(type_registry.clone());
+            scene
+                .write_to_world(&mut world, &mut EntityHashMap::default())
+                .unwrap_or_else(|e| panic!("{}: {e:?}", path.display()));
+
+            let mut q_solar_systems = world.query::<&SolarSystem>();
+            assert!(
+                q_solar_systems.iter(&world).count() > 0,
+                "{}",
+                path.display()
+            );
+
+            let mut q_ship = world.query::<(&Ship, &ChildOf)>();
+            assert!(q_ship.single(&world).is_ok(), "{}", path.display());
+        }
+
+        for version in 0..=SAVE_FORMAT_VERSION {
+            assert!(
+                versions.contains(&version),
+                "no fixture for version {version}"
+            );
+        }
+    }
+
+    #[test]
+    fn newer_saves_are_rejected() {
+        let content = write_save(
+            &SaveHeader {
+                version: SAVE_FORMAT_VERSION + 1,
+                ..default()
+            },
+            "(resources: {}, entities: {})",
+        )
+        .unwrap();
+
+        assert!(matches!(
+            read_save(&content, &TypeRegistry::new()),
+            Err(SaveError::UnsupportedVersion(_))
+        ));
+    }
+}
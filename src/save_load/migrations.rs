@@ -0,0 +1,240 @@
+use std::ops::Range;
+
+use bevy::scene::ron;
+
+// Migrations edit the RON text of the scene: older scenes can't be deserialized into the current types
+
+struct Entry {
+    key: Range<usize>,
+    value: Range<usize>,
+    end: usize, // after the trailing comma, if any
+}
+
+fn skip_whitespace(ron: &str, mut i: usize) -> usize {
+    while ron[i..].starts_with(char::is_whitespace) {
+        i += 1;
+    }
+    i
+}
+
+fn string_end(ron: &str, start: usize) -> usize {
+    let mut escaped = false;
+
+    for (i, c) in ron[start + 1..].char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => return start + 1 + i + 1,
+            _ => {}
+        }
+    }
+
+    ron.len()
+}
+
+// End of the value starting at `start`: the next comma or closing bracket outside of it
+fn value_end(ron: &str, start: usize) -> usize {
+    let mut depth = 0;
+    let mut i = start;
+    let mut end = start;
+
+    while i < ron.len() {
+        let c = ron[i..].chars().next().unwrap();
+
+        match c {
+            '"' => {
+                i = string_end(ron, i);
+                end = i;
+                continue;
+            }
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' | ',' if depth == 0 => break,
+            ')' | ']' | '}' => depth -= 1,
+            _ => {}
+        }
+
+        i += c.len_utf8();
+
+        if !c.is_whitespace() {
+            end = i;
+        }
+    }
+
+    end
+}
+
+// Entries of the map or struct whose opening bracket is at `open`
+fn entries(ron: &str, open: usize) -> Vec<Entry> {
+    let mut entries = vec![];
+    let mut i = skip_whitespace(ron, open + 1);
+
+    while i < ron.len() && !ron[i..].starts_with([')', '}']) {
+        let key_end = if ron[i..].starts_with('"') {
+            string_end(ron, i)
+        } else {
+            i + ron[i..]
+                .find(|c: char| !c.is_alphanumeric() && c != '_')
+                .unwrap_or(ron.len() - i)
+        };
+
+        let value_start = skip_whitespace(ron, skip_whitespace(ron, key_end) + 1);
+        let value_end = value_end(ron, value_start);
+
+        let mut end = skip_whitespace(ron, value_end);
+        if ron[end..].starts_with(',') {
+            end += 1;
+        }
+
+        entries.push(Entry {
+            key: i..key_end,
+            value: value_start..value_end,
+            end,
+        });
+
+        i = skip_whitespace(ron, end);
+    }
+
+    entries
+}
+
+// Elements of the list whose opening bracket is at `open`
+fn elements(ron: &str, open: usize) -> Vec<Range<usize>> {
+    let mut elements = vec![];
+    let mut i = skip_whitespace(ron, open + 1);
+
+    while i < ron.len() && !ron[i..].starts_with(']') {
+        let end = value_end(ron, i);
+        elements.push(i..end);
+
+        i = skip_whitespace(ron, end);
+        if ron[i..].starts_with(',') {
+            i = skip_whitespace(ron, i + 1);
+        }
+    }
+
+    elements
+}
+
+fn field(ron: &str, open: usize, key: &str) -> Option<Entry> {
+    entries(ron, open)
+        .into_iter()
+        .find(|entry| ron[entry.key.clone()].trim_matches('"') == key)
+}
+
+// (components of the entity, entity entry) of every entity of the scene
+fn entities(scene: &str) -> Vec<(Entry, Entry)> {
+    let Some(entities) = field(scene, skip_whitespace(scene, 0), "entities") else {
+        return vec![];
+    };
+
+    entries(scene, entities.value.start)
+        .into_iter()
+        .filter_map(|entity| {
+            field(scene, entity.value.start, "components").map(|components| (components, entity))
+        })
+        .collect()
+}
+
+// Applies the insertions and removals, given as (range replaced, new text), from the end of the scene
+fn edit(mut scene: String, mut edits: Vec<(Range<usize>, String)>) -> String {
+    edits.sort_by_key(|(range, _)| std::cmp::Reverse(range.start));
+
+    for (range, text) in edits {
+        scene.replace_range(range, &text);
+    }
+
+    scene
+}
+
+// Entities holding the given component are removed, along with all their other components
+pub(super) fn remove_entities_with(scene: String, type_path: &str) -> String {
+    let edits = entities(&scene)
+        .into_iter()
+        .filter(|(components, _)| field(&scene, components.value.start, type_path).is_some())
+        .map(|(_, entity)| (entity.key.start..entity.end, String::new()))
+        .collect();
+
+    edit(scene, edits)
+}
+
+// Adds the fields DormantSolarSystem and its ProceduralDeltas gained in version 2
+pub(super) fn complete_dormant_solar_systems(scene: String) -> String {
+    let mut edits = vec![];
+
+    for (components, _) in entities(&scene) {
+        let Some(dormant) = field(
+            &scene,
+            components.value.start,
+            "astras::universe::dormant_solar_system::DormantSolarSystem",
+        ) else {
+            continue;
+        };
+
+        let open = dormant.value.start;
+
+        if let Some(deltas) = field(&scene, open, "deltas") {
+            for delta in elements(&scene, deltas.value.start) {
+                if field(&scene, delta.start, "worm").is_none() {
+                    edits.push((delta.start + 1..delta.start + 1, "worm: None, ".to_string()));
+                }
+            }
+        }
+
+        // Version 1 only saved buildings and wrecks in the buildings scene
+        if field(&scene, open, "nb_buildings").is_none() {
+            let nb_buildings = field(&scene, open, "buildings")
+                .and_then(|buildings| ron::from_str::<String>(&scene[buildings.value]).ok())
+                .map_or(0, |buildings| entities(&buildings).len());
+
+            edits.push((
+                open + 1..open + 1,
+                format!("nb_buildings: {nb_buildings}, "),
+            ));
+        }
+    }
+
+    edit(scene, edits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entities_holding_a_component_are_removed() {
+        let scene = r#"(
+  resources: {},
+  entities: {
+    1: (components: {"a::A": (x: "),"), "a::B": ()}),
+    2: (components: {"a::B": ()}),
+  },
+)"#;
+
+        let migrated = remove_entities_with(scene.to_string(), "a::A");
+
+        assert_eq!(entities(&migrated).len(), 1);
+        assert!(!migrated.contains("a::A"));
+        assert!(migrated.contains(r#"2: (components: {"a::B": ()})"#));
+    }
+
+    #[test]
+    fn dormant_solar_systems_are_completed() {
+        let scene = r#"(
+  resources: {},
+  entities: {
+    1: (components: {"astras::universe::dormant_solar_system::DormantSolarSystem": (
+      deltas: [(id: 1, transform: None, inventory: None), (id: 2, transform: None, inventory: None)],
+      removed: [],
+      buildings: "(resources: {}, entities: {3: (components: {}), 4: (components: {})})",
+      anchors: [],
+      root: 1,
+    )}),
+  },
+)"#;
+
+        let migrated = complete_dormant_solar_systems(scene.to_string());
+
+        assert_eq!(migrated.matches("worm: None").count(), 2);
+        assert!(migrated.contains("nb_buildings: 2"));
+    }
+}
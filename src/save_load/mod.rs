@@ -0,0 +1,422 @@
+use std::{any::TypeId, path::PathBuf};
+
+use bevy::{
+    camera::{CameraMainTextureUsages, visibility::VisibilityClass},
+    ecs::{entity::EntityHashMap, system::SystemState},
+    platform::collections::HashMap,
+    prelude::*,
+    render::camera::CameraRenderGraph,
+    scene::DynamicEntity,
+    tasks::{IoTaskPool, Task, futures::check_ready},
+};
+
+use crate::{
+    GameState,
+    buildings::{BuildingHighlight, repair_logistic_references},
+    ui::{Hud, NotificationEvent},
+    universe::{
+        AsteroidMaterial, BackgroundMaterial, DormantSolarSystem, LaserMaterial, PlanetMaterial,
+        Ship, SolarSystem, StarMaterial, snapshot_solar_system,
+    },
+};
+
+mod format;
+mod migrations;
+mod slots;
+
+pub use format::*;
+pub use slots::*;
+
+pub const SAVES_DIR: &str = "saves";
+pub const SAVE_EXTENSION: &str = "scn.ron";
+pub const AUTOSAVE_INTERVAL: f32 = 300.0;
+
+#[derive(Resource)]
+pub struct UniverseName(pub String);
+
+// Seed of the first solar system of the universe
+#[derive(Resource, Default)]
+pub struct UniverseSeed(pub u64);
+
+#[derive(Resource, Default)]
+pub struct UniverseClock {
+    pub game_time: f64, // seconds
+    pub play_time: f64, // seconds
+}
+
+pub fn update_universe_clock(
+    time: Res<Time>,
+    real_time: Res<Time<Real>>,
+    mut clock: ResMut<UniverseClock>,
+) {
+    clock.game_time += time.delta_secs_f64();
+    clock.play_time += real_time.delta_secs_f64();
+}
+
+#[derive(Event)]
+pub struct LoadUniverse(pub PathBuf);
+
+#[derive(Default)]
+pub struct SaveUniverse {
+    pub autosave_slot: Option<usize>,
+}
+
+#[derive(Component)]
+pub struct SaveTask(Task<Result<PathBuf, SaveError>>);
+
+#[derive(Resource)]
+pub struct Autosave(Timer);
+
+impl Default for Autosave {
+    fn default() -> Self {
+        Self(Timer::from_seconds(AUTOSAVE_INTERVAL, TimerMode::Repeating))
+    }
+}
+
+impl Command for SaveUniverse {
+    fn apply(self, world: &mut World) {
+        let solar_systems = world
+            .query_filtered::<Entity, (With<SolarSystem>, Without<DormantSolarSystem>)>()
+            .iter(world)
+            .collect::<Vec<_>>();
+
+        // Procedural content is regenerated on load: only save the deltas and the player-made entities
+        let mut snapshots = HashMap::new();
+        for solar_system in solar_systems {
+            if let Some(snapshot) = snapshot_solar_system(world, solar_system) {
+                snapshots.insert(solar_system, snapshot);
+            }
+        }
+
+        let metadata = save_metadata(world);
+
+        let mut system_state: SystemState<(
+            Query<Entity, With<SolarSystem>>,
+            Query<&Children, Without<Ship>>, // Filter out the Ship children (sprite, camera)
+            Single<(Entity, &GlobalTransform), With<Ship>>,
+            Query<&ChildOf>,
+            Query<&GlobalTransform>,
+        )> = SystemState::new(world);
+
+        let (q_solar_systems, q_children, ship, q_child_of, q_global_transforms) =
+            system_state.get_mut(world);
+
+        let mut entities = vec![];
+        for solar_system in &q_solar_systems {
+            entities.push(solar_system);
+
+            // Solar systems generated before ProceduralIds existed are saved entirely
+            if !snapshots.contains_key(&solar_system) {
+                for child in q_children.iter_descendants(solar_system) {
+                    entities.push(child);
+                }
+            }
+        }
+
+        // The Ship is saved as a direct child of its solar system, as the astre it is docked on may not be saved
+        let (ship_entity, ship_global_transform) = *ship;
+        let ship_solar_system = q_child_of
+            .iter_ancestors(ship_entity)
+            .find(|e| q_solar_systems.contains(*e));
+        let ship_transform = ship_solar_system
+            .and_then(|e| q_global_transforms.get(e).ok())
+            .map(|t| ship_global_transform.reparented_to(t));
+
+        if !entities.contains(&ship_entity) {
+            entities.push(ship_entity);
+        }
+
+        let universe_name = metadata.universe_name.clone();
+
+        let path = match self.autosave_slot {
+            Some(slot) => {
+                info!("Autosaving universe {universe_name} in slot {slot}");
+                autosave_path(&universe_name, slot)
+            }
+            None => {
+                info!("Saving universe {universe_name}");
+                world.trigger(NotificationEvent(format!(
+                    "Saving universe {universe_name}"
+                )));
+                save_path(&universe_name)
+            }
+        };
+
+        let type_registry_arc = &**world.resource::<AppTypeRegistry>();
+        let type_registry = type_registry_arc.read();
+
+        let mut scene = save_scene_builder(world)
+            .extract_resources()
+            .extract_entities(entities.into_iter())
+            .remove_empty_entities()
+            .build();
+
+        for dynamic_entity in &mut scene.entities {
+            if let Some(snapshot) = snapshots.remove(&dynamic_entity.entity) {
+                // Children are not saved, they are regenerated
+                set_scene_component::<Children>(dynamic_entity, None);
+                set_scene_component(dynamic_entity, Some(snapshot));
+            } else if dynamic_entity.entity == ship_entity {
+                set_scene_component::<Children>(dynamic_entity, None);
+
+                if let (Some(solar_system), Some(transform)) = (ship_solar_system, ship_transform) {
+                    set_scene_component(dynamic_entity, Some(ChildOf(solar_system)));
+                    set_scene_component(dynamic_entity, Some(transform));
+                }
+            }
+        }
+
+        let serialized = scene
+            .serialize(&type_registry)
+            .map_err(|e| SaveError::Scene(e.to_string()))
+            .and_then(|scene| {
+                write_save(
+                    &SaveHeader {
+                        metadata,
+                        ..default()
+                    },
+                    &scene,
+                )
+            });
+
+        drop(type_registry);
+
+        match serialized {
+            Ok(serialized) => {
+                let task = IoTaskPool::get()
+                    .spawn(async move { write_save_file(&path, &serialized).map(|()| path) });
+
+                world.spawn(SaveTask(task));
+            }
+            Err(e) => {
+                error!("Error while serializing the scene: {e}");
+                world.trigger(NotificationEvent(format!("Can't save universe: {e}")));
+            }
+        }
+    }
+}
+
+pub fn poll_save_tasks(mut commands: Commands, mut q_save_tasks: Query<(Entity, &mut SaveTask)>) {
+    for (entity, mut save_task) in &mut q_save_tasks {
+        let Some(result) = check_ready(&mut save_task.0) else {
+            continue;
+        };
+
+        match result {
+            Ok(path) => info!("Saved {}", path.display()),
+            Err(e) => {
+                error!("Error while writing the save file: {e}");
+                commands.trigger(NotificationEvent(format!("Can't save universe: {e}")));
+            }
+        }
+
+        commands.entity(entity).despawn();
+    }
+}
+
+pub fn autosave(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    mut autosave: ResMut<Autosave>,
+    universe_name: Res<UniverseName>,
+) {
+    if autosave.0.tick(time.delta()).just_finished() {
+        commands.queue(SaveUniverse {
+            autosave_slot: Some(next_autosave_slot(&universe_name.0)),
+        });
+    }
+}
+
+fn save_metadata(world: &mut World) -> SaveMetadata {
+    let clock = world.resource::<UniverseClock>();
+    let (game_time, play_time) = (clock.game_time, clock.play_time);
+
+    let visited_systems = world
+        .query_filtered::<(), With<SolarSystem>>()
+        .iter(world)
+        .count();
+
+    let buildings = world
+        .query_filtered::<(), With<BuildingHighlight>>()
+        .iter(world)
+        .count()
+        + world
+            .query::<&DormantSolarSystem>()
+            .iter(world)
+            .map(DormantSolarSystem::nb_buildings)
+            .sum::<usize>();
+
+    SaveMetadata {
+        universe_name: world.resource::<UniverseName>().0.clone(),
+        seed: world.resource::<UniverseSeed>().0,
+        timestamp: std::time::UNIX_EPOCH.elapsed().unwrap().as_secs(),
+        game_time,
+        play_time,
+        visited_systems,
+        buildings,
+    }
+}
+
+// Replaces (or removes, if None) a component of an entity in an already built scene
+fn set_scene_component<C: Component + Reflect>(
+    dynamic_entity: &mut DynamicEntity,
+    component: Option<C>,
+) {
+    dynamic_entity.components.retain(|c| {
+        c.get_represented_type_info()
+            .is_none_or(|info| info.type_id() != TypeId::of::<C>())
+    });
+
+    if let Some(component) = component {
+        dynamic_entity.components.push(Box::new(component));
+    }
+}
+
+// Scene builder that skips the components rebuilt at runtime (render state, meshes, sprites)
+pub fn save_scene_builder(world: &World) -> DynamicSceneBuilder<'_> {
+    DynamicSceneBuilder::from_world(world)
+        .deny_all_resources()
+        .allow_all_components()
+        .deny_component::<CameraRenderGraph>()
+        .deny_component::<CameraMainTextureUsages>()
+        .deny_component::<MeshMaterial2d<PlanetMaterial>>()
+        .deny_component::<MeshMaterial2d<StarMaterial>>()
+        .deny_component::<MeshMaterial2d<AsteroidMaterial>>()
+        .deny_component::<MeshMaterial2d<LaserMaterial>>()
+        .deny_component::<MeshMaterial2d<BackgroundMaterial>>()
+        .deny_component::<Mesh2d>()
+        .deny_component::<Sprite>()
+        .deny_component::<VisibilityClass>() // contains a TypeId that is not serializable
+}
+
+pub fn load_universe(
+    load_universe: On<LoadUniverse>,
+    mut commands: Commands,
+    type_registry: Res<AppTypeRegistry>,
+) {
+    let path = &load_universe.0;
+
+    let Some(universe_name) = universe_name_from_path(path) else {
+        error!("Invalid save path {}", path.display());
+        return;
+    };
+
+    // Older saves are migrated to the current format
+    let (header, scene) = match std::fs::read_to_string(path)
+        .map_err(SaveError::from)
+        .and_then(|content| read_save(&content, &type_registry.read()))
+    {
+        Ok(save) => save,
+        Err(e) => {
+            error!("Error while loading {}: {e}", path.display());
+            commands.trigger(NotificationEvent(format!(
+                "Can't load universe {universe_name}: {e}"
+            )));
+            return;
+        }
+    };
+
+    commands.queue(SpawnUniverse {
+        universe_name,
+        header,
+        scene,
+    });
+}
+
+// Replaces the current universe by a loaded one, only if it can be spawned
+struct SpawnUniverse {
+    universe_name: String,
+    header: SaveHeader,
+    scene: DynamicScene,
+}
+
+impl Command for SpawnUniverse {
+    fn apply(self, world: &mut World) {
+        let universe_name = self.universe_name;
+
+        // Spawn it in a scratch world first, so the current universe is kept if the save is invalid
+        let mut scratch = World::new();
+        scratch.insert_resource(world.resource::<AppTypeRegistry>().clone());
+
+        let validation = self
+            .scene
+            .write_to_world(&mut scratch, &mut EntityHashMap::default())
+            .map_err(|e| SaveError::Scene(e.to_string()))
+            .and_then(|()| {
+                let nb_ships = scratch.query::<&Ship>().iter(&scratch).count();
+                let nb_solar_systems = scratch.query::<&SolarSystem>().iter(&scratch).count();
+
+                if nb_ships == 1 && nb_solar_systems > 0 {
+                    Ok(())
+                } else {
+                    Err(SaveError::Scene(format!(
+                        "{nb_ships} ships and {nb_solar_systems} solar systems"
+                    )))
+                }
+            });
+
+        if let Err(e) = validation {
+            error!("Error while spawning universe {universe_name}: {e}");
+            world.trigger(NotificationEvent(format!(
+                "Can't load universe {universe_name}: {e}"
+            )));
+            return;
+        }
+
+        // Remove all SolarSystems
+        let solar_systems = world
+            .query_filtered::<Entity, With<SolarSystem>>()
+            .iter(world)
+            .collect::<Vec<_>>();
+
+        // HUD will be recreated when Ship is Added<>
+        let huds = world
+            .query_filtered::<Entity, With<Hud>>()
+            .iter(world)
+            .collect::<Vec<_>>();
+
+        for entity in solar_systems.into_iter().chain(huds) {
+            world.despawn(entity);
+        }
+
+        if let Err(e) = self
+            .scene
+            .write_to_world(world, &mut EntityHashMap::default())
+        {
+            error!("Error while spawning universe {universe_name}: {e}");
+            return;
+        }
+
+        repair_logistic_references(world);
+
+        let metadata = self.header.metadata;
+        world.insert_resource(UniverseName(universe_name.clone()));
+        world.insert_resource(UniverseSeed(metadata.seed));
+        world.insert_resource(Autosave::default());
+        world.insert_resource(UniverseClock {
+            game_time: metadata.game_time,
+            play_time: metadata.play_time,
+        });
+
+        info!("Loading {universe_name}");
+
+        world
+            .resource_mut::<NextState<GameState>>()
+            .set(GameState::GameSolarSystem);
+    }
+}
+
+// `--load <path>` command line argument
+pub fn load_from_command_line(mut commands: Commands) {
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        if arg == "--load" {
+            if let Some(path) = args.next() {
+                commands.trigger(LoadUniverse(PathBuf::from(path)));
+            } else {
+                error!("--load expects a save file path");
+            }
+        }
+    }
+}
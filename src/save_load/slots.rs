@@ -0,0 +1,154 @@
+use std::{
+    fs::{self, File},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use crate::{SAVE_EXTENSION, SAVE_FORMAT_VERSION, SAVES_DIR, SaveError, read_save_header};
+
+pub const AUTOSAVE_SLOTS: usize = 3;
+const AUTOSAVE_SUFFIX: &str = ".autosave";
+
+pub fn save_path(universe_name: &str) -> PathBuf {
+    PathBuf::from(format!(
+        "assets/{SAVES_DIR}/{universe_name}.{SAVE_EXTENSION}"
+    ))
+}
+
+pub fn autosave_path(universe_name: &str, slot: usize) -> PathBuf {
+    save_path(&format!("{universe_name}{AUTOSAVE_SUFFIX}{slot}"))
+}
+
+// Autosaves belong to the universe they were made from
+pub fn universe_name_from_path(path: &Path) -> Option<String> {
+    let file_name = path.file_name()?.to_str()?;
+    let name = file_name
+        .strip_suffix(&format!(".{SAVE_EXTENSION}"))
+        .unwrap_or(file_name);
+
+    let name = name
+        .rsplit_once(AUTOSAVE_SUFFIX)
+        .filter(|(_, slot)| slot.parse::<usize>().is_ok())
+        .map_or(name, |(name, _)| name);
+
+    Some(name.to_string())
+}
+
+// Empty slot first, then the slot with the oldest autosave
+pub fn next_autosave_slot(universe_name: &str) -> usize {
+    (0..AUTOSAVE_SLOTS)
+        .min_by_key(|slot| {
+            fs::metadata(autosave_path(universe_name, *slot))
+                .and_then(|metadata| metadata.modified())
+                .ok()
+        })
+        .unwrap_or_default()
+}
+
+// Writes to a temporary file first, so a crash while saving never leaves a truncated save
+pub fn write_save_file(path: &Path, content: &str) -> Result<(), SaveError> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let tmp_path = PathBuf::from(format!("{}.tmp", path.display()));
+
+    let mut file = File::create(&tmp_path)?;
+    file.write_all(content.as_bytes())?;
+    file.sync_all()?;
+
+    fs::rename(&tmp_path, path)?;
+
+    Ok(())
+}
+
+// Universe names are file names: reject anything that could escape the saves directory
+fn check_universe_name(universe_name: &str) -> Result<(), SaveError> {
+    if universe_name.is_empty()
+        || universe_name.starts_with('.')
+        || universe_name.contains(['/', '\\', ':'])
+    {
+        return Err(SaveError::InvalidName(universe_name.to_string()));
+    }
+
+    Ok(())
+}
+
+// Appends _2, _3... until no save has this name
+fn free_universe_name(universe_name: &str) -> String {
+    let mut name = universe_name.to_string();
+    let mut i = 1;
+
+    while save_path(&name).exists() {
+        i += 1;
+        name = format!("{universe_name}_{i}");
+    }
+
+    name
+}
+
+pub fn delete_save(universe_name: &str) -> Result<(), SaveError> {
+    check_universe_name(universe_name)?;
+
+    fs::remove_file(save_path(universe_name))?;
+
+    // Autosaves of the universe go with it
+    for slot in 0..AUTOSAVE_SLOTS {
+        let path = autosave_path(universe_name, slot);
+
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+    }
+
+    Ok(())
+}
+
+pub fn rename_save(universe_name: &str, new_name: &str) -> Result<(), SaveError> {
+    check_universe_name(universe_name)?;
+    check_universe_name(new_name)?;
+
+    if save_path(new_name).exists() {
+        return Err(SaveError::AlreadyExists(new_name.to_string()));
+    }
+
+    fs::rename(save_path(universe_name), save_path(new_name))?;
+
+    for slot in 0..AUTOSAVE_SLOTS {
+        let path = autosave_path(universe_name, slot);
+
+        if path.exists() {
+            fs::rename(path, autosave_path(new_name, slot))?;
+        }
+    }
+
+    Ok(())
+}
+
+pub fn duplicate_save(universe_name: &str) -> Result<String, SaveError> {
+    check_universe_name(universe_name)?;
+
+    let new_name = free_universe_name(&format!("{universe_name}_copy"));
+    fs::copy(save_path(universe_name), save_path(&new_name))?;
+
+    Ok(new_name)
+}
+
+// Copies a save from anywhere on disk to the saves directory
+pub fn import_save(path: &Path) -> Result<String, SaveError> {
+    let header = read_save_header(path)?;
+
+    if header.version > SAVE_FORMAT_VERSION {
+        return Err(SaveError::UnsupportedVersion(header.version));
+    }
+
+    let universe_name = universe_name_from_path(path)
+        .ok_or_else(|| SaveError::InvalidName(path.display().to_string()))?;
+    check_universe_name(&universe_name)?;
+
+    let new_name = free_universe_name(&universe_name);
+    fs::create_dir_all(format!("assets/{SAVES_DIR}"))?;
+    fs::copy(path, save_path(&new_name))?;
+
+    Ok(new_name)
+}
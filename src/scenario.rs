@@ -0,0 +1,294 @@
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use bevy::{prelude::*, scene::ron};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Autosave, GameState, UniverseClock, UniverseName, UniverseSeed,
+    buildings::{BuildingHighlight, Crafter, place_building},
+    data::{BuildingId, ItemId, RecipeId},
+    items::{Inventory, LogisticProvider, LogisticRequest, LogisticScope},
+    ui::NotificationEvent,
+    universe::{
+        Ship, SolarSystem, assign_procedural_ids, build_ship, build_solar_system,
+        procedural_entities,
+    },
+};
+
+pub const SCENARIOS_DIR: &str = "scenarios";
+pub const SCENARIO_EXTENSION: &str = "scenario.ron";
+
+// Starting universe, written by hand in assets/scenarios
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[serde(default)]
+pub struct Scenario {
+    pub name: String,
+    pub description: String,
+    pub solar_systems: Vec<ScenarioSolarSystem>, // the Ship starts in the first one
+    pub ship_inventory: Vec<(ItemId, u32)>,
+    pub goals: Vec<ScenarioGoal>,
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[serde(default)]
+pub struct ScenarioSolarSystem {
+    pub position: [i32; 2], // also seeds its procedural generation
+    pub buildings: Vec<ScenarioBuilding>,
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[serde(default)]
+pub struct ScenarioBuilding {
+    pub building: BuildingId,
+    pub astre: u32, // procedural id of the astre in its solar system, the star is 0
+    pub position: [f32; 2], // relative to the astre
+    pub recipe: Option<RecipeId>,
+    pub inventory: Vec<(ItemId, u32)>,
+    pub logistic_request: Option<(LogisticScope, Vec<(ItemId, u32)>)>,
+    pub logistic_provider: Option<LogisticScope>,
+}
+
+#[derive(Serialize, Deserialize, Reflect, Debug, Clone)]
+pub enum ScenarioGoal {
+    // Items in the Ship inventory
+    ShipItems(ItemId, u32),
+    // Items stored in buildings, all solar systems together
+    StoredItems(ItemId, u32),
+}
+
+impl ScenarioGoal {
+    pub fn is_reached<'a>(
+        &self,
+        ship_inventory: &Inventory,
+        building_inventories: impl Iterator<Item = &'a Inventory>,
+    ) -> bool {
+        match self {
+            ScenarioGoal::ShipItems(id, quantity) => ship_inventory.quantity(*id) >= *quantity,
+            ScenarioGoal::StoredItems(id, quantity) => {
+                building_inventories
+                    .map(|inventory| inventory.quantity(*id))
+                    .sum::<u32>()
+                    >= *quantity
+            }
+        }
+    }
+}
+
+impl fmt::Display for ScenarioGoal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScenarioGoal::ShipItems(id, quantity) => {
+                write!(f, "Carry {quantity} {} in the ship", id.data().name)
+            }
+            ScenarioGoal::StoredItems(id, quantity) => {
+                write!(f, "Store {quantity} {} in buildings", id.data().name)
+            }
+        }
+    }
+}
+
+// Goals of the scenario the universe was started from, saved with the Ship
+#[derive(Component, Reflect, Default)]
+#[reflect(Component, Default)]
+pub struct ScenarioGoals {
+    pub goals: Vec<ScenarioGoal>,
+    pub completed: bool,
+}
+
+#[derive(Debug)]
+pub enum ScenarioError {
+    Io(std::io::Error),
+    Parse(String),
+    NoSolarSystem,
+    UnknownAstre([i32; 2], u32),
+}
+
+impl fmt::Display for ScenarioError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScenarioError::Io(e) => write!(f, "{e}"),
+            ScenarioError::Parse(e) => write!(f, "invalid scenario: {e}"),
+            ScenarioError::NoSolarSystem => write!(f, "the scenario has no solar system"),
+            ScenarioError::UnknownAstre(position, astre) => {
+                write!(f, "no astre {astre} in the solar system at {position:?}")
+            }
+        }
+    }
+}
+
+impl From<std::io::Error> for ScenarioError {
+    fn from(e: std::io::Error) -> Self {
+        ScenarioError::Io(e)
+    }
+}
+
+impl Scenario {
+    pub fn from_file(path: &Path) -> Result<Self, ScenarioError> {
+        let content = std::fs::read_to_string(path)?;
+        ron::from_str(&content).map_err(|e| ScenarioError::Parse(e.to_string()))
+    }
+
+    // Spawns the solar systems, their buildings and the Ship. Returns the Ship.
+    pub fn spawn(&self, world: &mut World) -> Result<Entity, ScenarioError> {
+        if self.solar_systems.is_empty() {
+            return Err(ScenarioError::NoSolarSystem);
+        }
+
+        let mut solar_system_entities = vec![];
+
+        for (i, solar_system) in self.solar_systems.iter().enumerate() {
+            let entity = world.spawn(build_solar_system(solar_system.position)).id();
+            assign_procedural_ids(world.entity_mut(entity));
+
+            // Only the starting solar system is visible, like the ones left after travelling
+            if i > 0 {
+                world.entity_mut(entity).insert(Visibility::Hidden);
+            }
+
+            solar_system_entities.push(entity);
+
+            let astres = procedural_entities(world, entity);
+
+            for building in &solar_system.buildings {
+                let Some(astre) = astres.get(&building.astre) else {
+                    for entity in solar_system_entities {
+                        world.entity_mut(entity).despawn();
+                    }
+                    return Err(ScenarioError::UnknownAstre(
+                        solar_system.position,
+                        building.astre,
+                    ));
+                };
+
+                spawn_scenario_building(world, *astre, building);
+            }
+        }
+
+        let ship = world
+            .spawn((
+                build_ship(),
+                ScenarioGoals {
+                    goals: self.goals.clone(),
+                    completed: false,
+                },
+                ChildOf(solar_system_entities[0]),
+            ))
+            .id();
+
+        // Items that don't fit in the cargo are dropped
+        let mut ship_inventory = world.get_mut::<Inventory>(ship).unwrap();
+        for (id, quantity) in &self.ship_inventory {
+            ship_inventory.try_add(*id, *quantity);
+        }
+
+        Ok(ship)
+    }
+}
+
+// Replaces the main menu by a new universe started from a scenario file
+pub struct StartScenario(pub PathBuf);
+
+impl Command for StartScenario {
+    fn apply(self, world: &mut World) {
+        let path = &self.0;
+
+        let scenario = match Scenario::from_file(path) {
+            Ok(scenario) => scenario,
+            Err(e) => {
+                error!("Can't load scenario {}: {e}", path.display());
+                world.trigger(NotificationEvent(format!("Can't load scenario: {e}")));
+                return;
+            }
+        };
+
+        if let Err(e) = scenario.spawn(world) {
+            error!("Can't start scenario {}: {e}", scenario.name);
+            world.trigger(NotificationEvent(format!(
+                "Can't start scenario {}: {e}",
+                scenario.name
+            )));
+            return;
+        }
+
+        info!("Starting scenario {}", scenario.name);
+
+        let file_name = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_suffix(&format!(".{SCENARIO_EXTENSION}")))
+            .unwrap_or("scenario");
+        let timestamp = std::time::UNIX_EPOCH.elapsed().unwrap().as_millis();
+
+        world.insert_resource(UniverseName(format!("{file_name}_{timestamp}")));
+        world.insert_resource(UniverseSeed(
+            SolarSystem {
+                position: scenario.solar_systems[0].position,
+            }
+            .seed(),
+        ));
+        world.insert_resource(UniverseClock::default());
+        world.insert_resource(Autosave::default());
+
+        world
+            .resource_mut::<NextState<GameState>>()
+            .set(GameState::GameSolarSystem);
+    }
+}
+
+fn spawn_scenario_building(world: &mut World, astre: Entity, building: &ScenarioBuilding) {
+    let entity = place_building(
+        world,
+        astre,
+        building.building,
+        Vec2::from(building.position),
+    );
+
+    if let Some((scope, items)) = &building.logistic_request {
+        world.entity_mut(entity).insert(LogisticRequest::new(
+            items.iter().copied().collect(),
+            *scope,
+        ));
+    }
+
+    if let Some(scope) = building.logistic_provider {
+        world
+            .entity_mut(entity)
+            .insert(LogisticProvider::new(scope));
+    }
+
+    if let Some(recipe) = building.recipe
+        && let Some(mut crafter) = world.get_mut::<Crafter>(entity)
+    {
+        crafter.set_recipe(recipe);
+    }
+
+    if let Some(mut inventory) = world.get_mut::<Inventory>(entity) {
+        for (id, quantity) in &building.inventory {
+            inventory.try_add(*id, *quantity);
+        }
+    }
+}
+
+pub fn check_scenario_goals(
+    mut commands: Commands,
+    ship: Single<(&Inventory, &mut ScenarioGoals), With<Ship>>,
+    q_buildings: Query<&Inventory, (With<BuildingHighlight>, Without<Ship>)>,
+) {
+    let (ship_inventory, mut scenario_goals) = ship.into_inner();
+
+    if scenario_goals.completed || scenario_goals.goals.is_empty() {
+        return;
+    }
+
+    if scenario_goals
+        .goals
+        .iter()
+        .all(|goal| goal.is_reached(ship_inventory, q_buildings.iter()))
+    {
+        scenario_goals.completed = true;
+        commands.trigger(NotificationEvent("Scenario complete!".to_string()));
+    }
+}
@@ -0,0 +1,340 @@
+use std::{
+    collections::VecDeque,
+    fmt,
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use bevy::{platform::collections::HashMap, prelude::*};
+
+use crate::{SolarSystemSet, data::ItemId, items::ItemMap, universe::SolarSystem};
+
+pub const STATISTICS_DIR: &str = "statistics";
+
+// Statistics are aggregated in buckets of this duration, the last hour being kept
+pub const STATISTICS_BUCKET_SECONDS: f32 = 10.;
+const STATISTICS_BUCKETS: usize = 360;
+
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum FlowKind {
+    Produced,  // crafted or extracted
+    Consumed,  // used by a recipe
+    Delivered, // brought by a freight
+}
+
+impl FlowKind {
+    pub const ALL: [FlowKind; 3] = [FlowKind::Produced, FlowKind::Consumed, FlowKind::Delivered];
+}
+
+impl fmt::Display for FlowKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FlowKind::Produced => write!(f, "produced"),
+            FlowKind::Consumed => write!(f, "consumed"),
+            FlowKind::Delivered => write!(f, "delivered"),
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Default, Debug)]
+pub enum StatisticsWindow {
+    #[default]
+    OneMinute,
+    TenMinutes,
+    OneHour,
+}
+
+impl StatisticsWindow {
+    pub const ALL: [StatisticsWindow; 3] = [
+        StatisticsWindow::OneMinute,
+        StatisticsWindow::TenMinutes,
+        StatisticsWindow::OneHour,
+    ];
+
+    pub fn buckets(self) -> usize {
+        let minutes = match self {
+            StatisticsWindow::OneMinute => 1.,
+            StatisticsWindow::TenMinutes => 10.,
+            StatisticsWindow::OneHour => 60.,
+        };
+        (minutes * 60. / STATISTICS_BUCKET_SECONDS) as usize
+    }
+}
+
+impl fmt::Display for StatisticsWindow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StatisticsWindow::OneMinute => write!(f, "1m"),
+            StatisticsWindow::TenMinutes => write!(f, "10m"),
+            StatisticsWindow::OneHour => write!(f, "1h"),
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum StatisticsScope {
+    Global,
+    SolarSystem([i32; 2]),
+    Building(Entity),
+}
+
+impl fmt::Display for StatisticsScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StatisticsScope::Global => write!(f, "global"),
+            StatisticsScope::SolarSystem([x, y]) => write!(f, "solar system {x} {y}"),
+            StatisticsScope::Building(entity) => write!(f, "building {entity}"),
+        }
+    }
+}
+
+// Item flows of one scope, the current bucket being the last one
+#[derive(Default, Clone, Debug)]
+pub struct FlowHistory {
+    buckets: VecDeque<HashMap<(FlowKind, ItemId), u32>>,
+}
+
+impl FlowHistory {
+    fn record(&mut self, kind: FlowKind, item: ItemId, quantity: u32) {
+        if self.buckets.is_empty() {
+            self.buckets.push_back(HashMap::new());
+        }
+
+        *self
+            .buckets
+            .back_mut()
+            .unwrap()
+            .entry((kind, item))
+            .or_default() += quantity;
+    }
+
+    fn advance(&mut self) {
+        self.buckets.push_back(HashMap::new());
+
+        while self.buckets.len() > STATISTICS_BUCKETS {
+            self.buckets.pop_front();
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.buckets.iter().all(HashMap::is_empty)
+    }
+
+    fn window(
+        &self,
+        window: StatisticsWindow,
+    ) -> impl Iterator<Item = &HashMap<(FlowKind, ItemId), u32>> {
+        self.buckets.iter().rev().take(window.buckets())
+    }
+
+    pub fn total(&self, window: StatisticsWindow, kind: FlowKind, item: ItemId) -> u32 {
+        self.window(window)
+            .filter_map(|bucket| bucket.get(&(kind, item)))
+            .sum()
+    }
+
+    pub fn totals(&self, window: StatisticsWindow, kind: FlowKind) -> ItemMap {
+        let mut totals = ItemMap::default();
+
+        for bucket in self.window(window) {
+            for ((bucket_kind, item), quantity) in bucket {
+                if *bucket_kind == kind {
+                    *totals.entry(*item).or_default() += quantity;
+                }
+            }
+        }
+
+        totals
+    }
+
+    // Items with a flow during the window, in the order of ItemId::ALL
+    pub fn items(&self, window: StatisticsWindow) -> Vec<ItemId> {
+        ItemId::ALL
+            .iter()
+            .filter(|item| {
+                FlowKind::ALL
+                    .iter()
+                    .any(|kind| self.total(window, *kind, **item) > 0)
+            })
+            .copied()
+            .collect()
+    }
+
+    // Quantities per bucket, oldest first, padded with zeros before the first bucket
+    pub fn series(&self, window: StatisticsWindow, kind: FlowKind, item: ItemId) -> Vec<u32> {
+        let mut series = self
+            .window(window)
+            .map(|bucket| bucket.get(&(kind, item)).copied().unwrap_or_default())
+            .collect::<Vec<_>>();
+        series.resize(window.buckets(), 0);
+        series.reverse();
+        series
+    }
+}
+
+#[derive(Resource)]
+pub struct ProductionStatistics {
+    bucket_timer: Timer,
+    global: FlowHistory,
+    solar_systems: HashMap<[i32; 2], FlowHistory>,
+    buildings: HashMap<Entity, FlowHistory>,
+    generation: u32, // number of buckets started, for the UI to know when to refresh
+}
+
+impl Default for ProductionStatistics {
+    fn default() -> Self {
+        Self {
+            bucket_timer: Timer::from_seconds(STATISTICS_BUCKET_SECONDS, TimerMode::Repeating),
+            global: FlowHistory::default(),
+            solar_systems: HashMap::new(),
+            buildings: HashMap::new(),
+            generation: 0,
+        }
+    }
+}
+
+impl ProductionStatistics {
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    pub fn history(&self, scope: StatisticsScope) -> Option<&FlowHistory> {
+        match scope {
+            StatisticsScope::Global => Some(&self.global),
+            StatisticsScope::SolarSystem(position) => self.solar_systems.get(&position),
+            StatisticsScope::Building(entity) => self.buildings.get(&entity),
+        }
+    }
+
+    fn scopes(&self) -> impl Iterator<Item = (StatisticsScope, &FlowHistory)> {
+        std::iter::once((StatisticsScope::Global, &self.global))
+            .chain(
+                self.solar_systems
+                    .iter()
+                    .map(|(position, history)| (StatisticsScope::SolarSystem(*position), history)),
+            )
+            .chain(
+                self.buildings
+                    .iter()
+                    .map(|(entity, history)| (StatisticsScope::Building(*entity), history)),
+            )
+    }
+
+    // One line per scope, window and item, for spreadsheets
+    pub fn write_csv(&self, writer: &mut impl Write) -> std::io::Result<()> {
+        write!(writer, "scope,window,item")?;
+        for kind in FlowKind::ALL {
+            write!(writer, ",{kind}")?;
+        }
+        writeln!(writer)?;
+
+        for (scope, history) in self.scopes() {
+            for window in StatisticsWindow::ALL {
+                for item in history.items(window) {
+                    write!(writer, "{scope},{window},{item:?}")?;
+                    for kind in FlowKind::ALL {
+                        write!(writer, ",{}", history.total(window, kind, item))?;
+                    }
+                    writeln!(writer)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn export_csv(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_csv(&mut writer)?;
+        writer.flush()
+    }
+}
+
+// Items produced, consumed or delivered by a building
+#[derive(Event)]
+pub struct ItemFlow {
+    pub building: Entity,
+    pub kind: FlowKind,
+    pub item: ItemId,
+    pub quantity: u32,
+}
+
+pub fn record_item_flow(
+    flow: On<ItemFlow>,
+    mut statistics: ResMut<ProductionStatistics>,
+    q_parents: Query<&ChildOf>,
+    q_solar_systems: Query<&SolarSystem>,
+) {
+    if flow.quantity == 0 {
+        return;
+    }
+
+    let statistics = statistics.as_mut();
+
+    statistics
+        .global
+        .record(flow.kind, flow.item, flow.quantity);
+
+    statistics
+        .buildings
+        .entry(flow.building)
+        .or_default()
+        .record(flow.kind, flow.item, flow.quantity);
+
+    // The building may already be despawned, e.g. a construction site
+    if let Some(solar_system) = q_parents
+        .iter_ancestors(flow.building)
+        .find_map(|ancestor| q_solar_systems.get(ancestor).ok())
+    {
+        statistics
+            .solar_systems
+            .entry(solar_system.position)
+            .or_default()
+            .record(flow.kind, flow.item, flow.quantity);
+    }
+}
+
+pub fn advance_statistics(
+    time: Res<Time>,
+    mut statistics: ResMut<ProductionStatistics>,
+    q_entities: Query<()>,
+) {
+    if !statistics.bucket_timer.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    let statistics = statistics.as_mut();
+
+    statistics.global.advance();
+    for history in statistics.solar_systems.values_mut() {
+        history.advance();
+    }
+
+    // Histories of destroyed buildings are dropped once their last flow leaves the hour
+    statistics.buildings.retain(|entity, history| {
+        history.advance();
+        q_entities.contains(*entity) || !history.is_empty()
+    });
+
+    statistics.generation += 1;
+}
+
+// Statistics only cover the universe being played
+pub fn reset_statistics(mut commands: Commands) {
+    commands.insert_resource(ProductionStatistics::default());
+}
+
+pub struct StatisticsPlugin;
+
+impl Plugin for StatisticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ProductionStatistics>()
+            .add_systems(Update, advance_statistics.in_set(SolarSystemSet))
+            .add_observer(record_item_flow);
+    }
+}
@@ -0,0 +1,226 @@
+use bevy::{ecs::spawn::SpawnWith, prelude::*};
+
+use crate::{
+    buildings::{BuildingAlert, Crafter, Extractor, LogisticFreight},
+    ui::{ClearUiEvent, Hud, HudWindow, HudWindowParent, UiButton, build_building_header},
+    universe::{MainCamera, focus_camera_on},
+};
+
+const ALERT_COLOR: Color = Color::srgb(0.9, 0.3, 0.1);
+const ALERT_ICON_SIZE: f32 = 20.;
+
+// Icon following a building with a raised alert
+#[derive(Component)]
+pub struct AlertIcon(Entity);
+
+// HUD button opening the alert list, showing the number of alerts
+#[derive(Component)]
+pub struct AlertCounter;
+
+type AlertedBuilding<'a> = (
+    Entity,
+    &'a BuildingAlert,
+    Option<&'a Name>,
+    Has<Crafter>,
+    Has<Extractor>,
+    Has<LogisticFreight>,
+);
+
+fn building_label(name: Option<&Name>, crafter: bool, extractor: bool, freight: bool) -> String {
+    match name {
+        Some(name) => name.to_string(),
+        None if crafter => "Crafter".to_string(),
+        None if extractor => "Extractor".to_string(),
+        None if freight => "Freight".to_string(),
+        None => "Building".to_string(),
+    }
+}
+
+pub fn setup_alert_counter(mut commands: Commands, hud: Single<Entity, Added<Hud>>) {
+    commands.entity(*hud).with_children(|c| {
+        c.spawn((
+            AlertCounter,
+            UiButton,
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(5.0),
+                right: Val::Px(5.0),
+                ..default()
+            },
+            Visibility::Hidden,
+            children![Text::default()],
+        ))
+        .observe(spawn_alert_list);
+    });
+}
+
+pub fn update_alert_counter(
+    counter: Single<(&Children, &mut Visibility), With<AlertCounter>>,
+    mut q_texts: Query<&mut Text>,
+    q_alerts: Query<(&BuildingAlert, &InheritedVisibility)>,
+) {
+    let (children, mut visibility) = counter.into_inner();
+
+    let count = q_alerts
+        .iter()
+        .filter(|(alert, inherited_visibility)| alert.is_raised() && inherited_visibility.get())
+        .count();
+
+    *visibility = if count > 0 {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+
+    let mut texts = q_texts.iter_many_mut(children);
+    while let Some(mut text) = texts.fetch_next() {
+        text.0 = format!("{count} alert(s)");
+    }
+}
+
+fn spawn_alert_list(
+    _pointer_click: On<Pointer<Click>>,
+    mut commands: Commands,
+    window_parent: Single<Entity, With<HudWindowParent>>,
+    q_alerts: Query<AlertedBuilding>,
+    q_visibilities: Query<&InheritedVisibility>,
+) {
+    // Only the buildings of the solar system being played are listed
+    let alerts = q_alerts
+        .iter()
+        .filter(|(entity, alert, ..)| {
+            alert.is_raised() && q_visibilities.get(*entity).is_ok_and(|v| v.get())
+        })
+        .map(|(entity, alert, name, crafter, extractor, freight)| {
+            (
+                entity,
+                format!(
+                    "{}: {}",
+                    building_label(name, crafter, extractor, freight),
+                    alert.kind()
+                ),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    commands
+        .entity(*window_parent)
+        .despawn_related::<Children>()
+        .with_children(|c| {
+            c.spawn((
+                HudWindow,
+                Children::spawn(SpawnWith(move |c: &mut ChildSpawner| {
+                    c.spawn(build_building_header("Alerts"));
+
+                    for (entity, message) in alerts {
+                        c.spawn(Node {
+                            align_items: AlignItems::Center,
+                            column_gap: Val::Px(10.0),
+                            margin: UiRect::bottom(Val::Px(5.0)),
+                            ..default()
+                        })
+                        .with_children(|c| {
+                            c.spawn(Text::new(message));
+                            c.spawn((UiButton, children![Text::new("Show")]))
+                                .observe(focus_alert_callback(entity));
+                        });
+                    }
+                })),
+            ));
+        });
+}
+
+fn focus_alert_callback(
+    entity: Entity,
+) -> impl FnMut(
+    On<Pointer<Click>>,
+    Commands,
+    Single<(&mut Projection, &GlobalTransform, &Camera), With<MainCamera>>,
+    Query<&GlobalTransform, Without<MainCamera>>,
+) {
+    move |_pointer_click, mut commands, camera, q_transforms| {
+        let (mut projection, camera_transform, camera) = camera.into_inner();
+
+        let (Ok(transform), Some(viewport_size)) =
+            (q_transforms.get(entity), camera.logical_viewport_size())
+        else {
+            return;
+        };
+
+        focus_camera_on(
+            &mut projection,
+            camera_transform,
+            viewport_size,
+            transform.translation().truncate(),
+        );
+
+        commands.trigger(ClearUiEvent);
+    }
+}
+
+pub fn spawn_alert_icons(
+    mut commands: Commands,
+    hud: Single<Entity, With<Hud>>,
+    q_alerts: Query<(Entity, &BuildingAlert)>,
+    q_alert_icons: Query<&AlertIcon>,
+) {
+    for (entity, alert) in &q_alerts {
+        if !alert.is_raised() || q_alert_icons.iter().any(|icon| icon.0 == entity) {
+            continue;
+        }
+
+        commands.entity(*hud).with_children(|c| {
+            c.spawn((
+                AlertIcon(entity),
+                Node {
+                    position_type: PositionType::Absolute,
+                    width: Val::Px(ALERT_ICON_SIZE),
+                    height: Val::Px(ALERT_ICON_SIZE),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                BorderRadius::MAX,
+                BackgroundColor(ALERT_COLOR),
+                Visibility::Hidden,
+                Pickable::IGNORE,
+                children![(Text::new("!"), Pickable::IGNORE)],
+            ));
+        });
+    }
+}
+
+// Icons follow their building on screen, and disappear with the alert
+pub fn update_alert_icons(
+    mut commands: Commands,
+    camera: Single<(&Camera, &GlobalTransform), With<MainCamera>>,
+    mut q_alert_icons: Query<(Entity, &AlertIcon, &mut Node, &mut Visibility)>,
+    q_alerts: Query<(&BuildingAlert, &GlobalTransform, &InheritedVisibility)>,
+) {
+    let (camera, camera_transform) = camera.into_inner();
+
+    for (icon_entity, alert_icon, mut node, mut visibility) in &mut q_alert_icons {
+        let Ok((alert, transform, inherited_visibility)) = q_alerts.get(alert_icon.0) else {
+            commands.entity(icon_entity).despawn();
+            continue;
+        };
+
+        if !alert.is_raised() {
+            commands.entity(icon_entity).despawn();
+            continue;
+        }
+
+        let position = camera
+            .world_to_viewport(camera_transform, transform.translation())
+            .ok()
+            .filter(|_| inherited_visibility.get());
+
+        if let Some(position) = position {
+            node.left = Val::Px(position.x - ALERT_ICON_SIZE / 2.);
+            node.top = Val::Px(position.y - ALERT_ICON_SIZE * 2.);
+            *visibility = Visibility::Inherited;
+        } else {
+            *visibility = Visibility::Hidden;
+        }
+    }
+}
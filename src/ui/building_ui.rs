@@ -1,6 +1,27 @@
 use bevy::{ecs::spawn::SpawnWith, prelude::*};
 
-use crate::ui::{ClearUiEvent, UiButton};
+use crate::{
+    buildings::{
+        BUILDING_MAX_HEALTH, BuildingCondition, BuildingDisabled, BuildingHealth, BuildingScript,
+        HEALTH_PER_ASTRIUM, SetBuildingCondition,
+    },
+    data::ItemId,
+    items::Inventory,
+    ui::{ClearUiEvent, NotificationEvent, UiButton, spawn_dialog},
+    universe::Ship,
+};
+
+// Health of the building shown in the HudWindow
+#[derive(Component)]
+pub struct BuildingHealthUI(Entity);
+
+// Name and script status of the building shown in the HudWindow
+#[derive(Component)]
+pub struct BuildingScriptUI(Entity);
+
+// Condition enabling the building, shown in the HudWindow
+#[derive(Component)]
+pub struct BuildingConditionUI(Entity);
 
 pub fn build_building_header(name: &str) -> impl Bundle {
     let name = name.to_string();
@@ -32,3 +53,232 @@ pub fn build_building_header(name: &str) -> impl Bundle {
         })),
     )
 }
+
+pub fn build_building_health_ui(entity: Entity) -> impl Bundle {
+    (
+        Node {
+            align_items: AlignItems::Center,
+            flex_direction: FlexDirection::Row,
+            column_gap: Val::Px(10.0),
+            margin: UiRect::bottom(Val::Px(10.0)),
+            ..default()
+        },
+        Children::spawn(SpawnWith(move |c: &mut ChildSpawner| {
+            c.spawn((BuildingHealthUI(entity), Text::default()));
+
+            c.spawn((UiButton, children![Text::new("Repair")]))
+                .observe(repair_building_callback(entity));
+        })),
+    )
+}
+
+// Repairs with the Astrium of the Ship, as much as it can afford
+fn repair_building_callback(
+    entity: Entity,
+) -> impl FnMut(
+    On<Pointer<Click>>,
+    Commands,
+    Single<(&Ship, &mut Inventory, &GlobalTransform)>,
+    Query<(&mut BuildingHealth, &GlobalTransform), Without<Ship>>,
+) {
+    move |_pointer_click, mut commands, q_ship, mut q_buildings| {
+        let (ship, mut ship_inventory, ship_transform) = q_ship.into_inner();
+
+        let Ok((mut health, transform)) = q_buildings.get_mut(entity) else {
+            return;
+        };
+
+        if ship_transform
+            .translation()
+            .distance(transform.translation())
+            >= ship.action_range()
+        {
+            commands.trigger(NotificationEvent(
+                "The ship is too far to repair".to_string(),
+            ));
+            return;
+        }
+
+        let needed = (health.missing_health() / HEALTH_PER_ASTRIUM).ceil() as u32;
+        let quantity = needed.min(ship_inventory.quantity(ItemId::Astrium));
+
+        if needed == 0 {
+            return;
+        }
+
+        if quantity == 0 {
+            commands.trigger(NotificationEvent(
+                "Not enough Astrium to repair".to_string(),
+            ));
+            return;
+        }
+
+        ship_inventory.try_remove(ItemId::Astrium, quantity);
+        health.repair(quantity as f32 * HEALTH_PER_ASTRIUM);
+    }
+}
+
+pub fn update_building_health_ui(
+    mut q_building_health_ui: Query<(&BuildingHealthUI, &mut Text)>,
+    q_buildings: Query<&BuildingHealth>,
+) {
+    for (building_health_ui, mut text) in &mut q_building_health_ui {
+        if let Ok(health) = q_buildings.get(building_health_ui.0) {
+            text.0 = format!(
+                "Health: {:.0}/{BUILDING_MAX_HEALTH} (efficiency {:.0}%)",
+                health.health(),
+                health.efficiency() * 100.
+            );
+        }
+    }
+}
+
+// Scripts read the inventories of the other buildings by their name
+pub fn build_building_script_ui(entity: Entity) -> impl Bundle {
+    (
+        Node {
+            align_items: AlignItems::Center,
+            flex_direction: FlexDirection::Row,
+            column_gap: Val::Px(10.0),
+            margin: UiRect::bottom(Val::Px(10.0)),
+            ..default()
+        },
+        Children::spawn(SpawnWith(move |c: &mut ChildSpawner| {
+            c.spawn((BuildingScriptUI(entity), Text::default()));
+
+            c.spawn((UiButton, children![Text::new("Rename")])).observe(
+                move |_pointer_click: On<Pointer<Click>>,
+                      mut commands: Commands,
+                      q_names: Query<&Name>| {
+                    let name = q_names.get(entity).map(ToString::to_string).ok();
+
+                    spawn_dialog(
+                        &mut commands,
+                        "Building name",
+                        Some(name.unwrap_or_default()),
+                        move |commands, name| {
+                            if name.is_empty() {
+                                commands.entity(entity).remove::<Name>();
+                            } else {
+                                commands.entity(entity).insert(Name::new(name));
+                            }
+                        },
+                    );
+                },
+            );
+
+            c.spawn((UiButton, children![Text::new("Edit script")]))
+                .observe(
+                    move |_pointer_click: On<Pointer<Click>>,
+                          mut commands: Commands,
+                          q_scripts: Query<&BuildingScript>| {
+                        let source = q_scripts
+                            .get(entity)
+                            .map(|script| script.source().to_string());
+
+                        spawn_dialog(
+                            &mut commands,
+                            "Rhai script, run every second (empty to remove)",
+                            Some(source.unwrap_or_default()),
+                            move |commands, source| {
+                                if source.is_empty() {
+                                    commands.entity(entity).remove::<BuildingScript>();
+                                } else {
+                                    commands.entity(entity).insert(BuildingScript::new(source));
+                                }
+                            },
+                        );
+                    },
+                );
+        })),
+    )
+}
+
+pub fn update_building_script_ui(
+    mut q_building_script_ui: Query<(&BuildingScriptUI, &mut Text)>,
+    q_buildings: Query<(Option<&Name>, Option<&BuildingScript>)>,
+) {
+    for (building_script_ui, mut text) in &mut q_building_script_ui {
+        let Ok((name, script)) = q_buildings.get(building_script_ui.0) else {
+            continue;
+        };
+
+        let name = name.map_or("Unnamed", Name::as_str);
+        let status = match script {
+            None => "no script".to_string(),
+            Some(script) => match script.last_error() {
+                None => "script running".to_string(),
+                Some(e) => format!("script error: {e}"),
+            },
+        };
+
+        text.0 = format!("{name}, {status}");
+    }
+}
+
+pub fn build_building_condition_ui(entity: Entity) -> impl Bundle {
+    (
+        Node {
+            align_items: AlignItems::Center,
+            flex_direction: FlexDirection::Row,
+            column_gap: Val::Px(10.0),
+            margin: UiRect::bottom(Val::Px(10.0)),
+            ..default()
+        },
+        Children::spawn(SpawnWith(move |c: &mut ChildSpawner| {
+            c.spawn((BuildingConditionUI(entity), Text::default()));
+
+            c.spawn((UiButton, children![Text::new("Condition")]))
+                .observe(
+                    move |_pointer_click: On<Pointer<Click>>,
+                          mut commands: Commands,
+                          q_conditions: Query<&BuildingCondition>,
+                          q_names: Query<&Name>| {
+                        let current = q_conditions.get(entity).ok().map(|condition| {
+                            format_condition(condition, q_names.get(condition.inventory).ok())
+                        });
+
+                        spawn_dialog(
+                            &mut commands,
+                            "Enable when <building name> <item> <|> <threshold> (empty to remove)",
+                            Some(current.unwrap_or_default()),
+                            move |commands, input| {
+                                commands.queue(SetBuildingCondition(entity, input));
+                            },
+                        );
+                    },
+                );
+        })),
+    )
+}
+
+fn format_condition(condition: &BuildingCondition, name: Option<&Name>) -> String {
+    format!(
+        "{} {:?} {} {}",
+        name.map_or("?", Name::as_str),
+        condition.item,
+        condition.comparison,
+        condition.threshold
+    )
+}
+
+pub fn update_building_condition_ui(
+    mut q_building_condition_ui: Query<(&BuildingConditionUI, &mut Text)>,
+    q_buildings: Query<(Option<&BuildingCondition>, Has<BuildingDisabled>)>,
+    q_names: Query<&Name>,
+) {
+    for (building_condition_ui, mut text) in &mut q_building_condition_ui {
+        let Ok((condition, disabled)) = q_buildings.get(building_condition_ui.0) else {
+            continue;
+        };
+
+        text.0 = match condition {
+            None => "Always enabled".to_string(),
+            Some(condition) => format!(
+                "{} when {}",
+                if disabled { "Disabled" } else { "Enabled" },
+                format_condition(condition, q_names.get(condition.inventory).ok())
+            ),
+        };
+    }
+}
@@ -4,7 +4,11 @@ use crate::{
     buildings::Crafter,
     data::{BuildingId, ItemId},
     items::RecipeOutputs,
-    ui::{HudWindow, HudWindowParent, InventoryUI, UiButton, build_building_header, build_item_ui},
+    ui::{
+        HudWindow, HudWindowParent, InventoryUI, UiButton, build_building_condition_ui,
+        build_building_header, build_building_health_ui, build_building_script_ui,
+        build_building_statistics_ui, build_item_ui,
+    },
 };
 
 pub fn scan_crafter_ui(mut commands: Commands, q_crafters: Query<Entity, Added<Crafter>>) {
@@ -30,6 +34,10 @@ fn spawn_crafter_ui(
             c.spawn(HudWindow).with_children(|c| {
                 let name = if crafter.is_construction_site() { "Construction site" } else { "Crafter" };
                 c.spawn(build_building_header(name));
+                c.spawn(build_building_health_ui(entity));
+                c.spawn(build_building_statistics_ui(entity));
+                c.spawn(build_building_script_ui(entity));
+                c.spawn(build_building_condition_ui(entity));
 
                 if !crafter.is_construction_site() {
                     // List recipes
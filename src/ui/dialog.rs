@@ -0,0 +1,95 @@
+use bevy::prelude::*;
+
+use crate::ui::{TextInput, UiButton};
+
+#[derive(Component)]
+pub struct Dialog;
+
+// Button of the dialog, closing it when clicked
+#[derive(Component)]
+pub struct DialogUI(Entity);
+
+// Modal confirmation window. If `input` is Some, a text field prefilled with it is shown and its value is given to `on_confirm`.
+pub fn spawn_dialog(
+    commands: &mut Commands,
+    message: impl Into<String>,
+    input: Option<String>,
+    on_confirm: impl Fn(&mut Commands, String) + Send + Sync + 'static,
+) {
+    let message = message.into();
+
+    commands
+        .spawn((
+            Dialog,
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            BackgroundColor(Color::BLACK.with_alpha(0.7)),
+            GlobalZIndex(10),
+        ))
+        .with_children(|c| {
+            let dialog = c.target_entity();
+
+            c.spawn((
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(10.0),
+                    padding: UiRect::all(Val::Px(20.0)),
+                    ..default()
+                },
+                BackgroundColor(Color::srgb(0.1, 0.1, 0.1)),
+            ))
+            .with_children(|c| {
+                c.spawn(Text::new(message));
+
+                if let Some(input) = input {
+                    c.spawn(TextInput(input));
+                }
+
+                c.spawn(Node {
+                    column_gap: Val::Px(10.0),
+                    ..default()
+                })
+                .with_children(|c| {
+                    c.spawn((DialogUI(dialog), UiButton, children![Text::new("Confirm")]))
+                        .observe(
+                            move |pointer_click: On<Pointer<Click>>,
+                                  mut commands: Commands,
+                                  q_dialog_ui: Query<&DialogUI>,
+                                  q_children: Query<&Children>,
+                                  q_text_inputs: Query<&TextInput>| {
+                                let Ok(dialog_ui) = q_dialog_ui.get(pointer_click.entity) else {
+                                    return;
+                                };
+
+                                let input = q_children
+                                    .iter_descendants(dialog_ui.0)
+                                    .find_map(|entity| q_text_inputs.get(entity).ok())
+                                    .map(|text_input| text_input.0.trim().to_string())
+                                    .unwrap_or_default();
+
+                                on_confirm(&mut commands, input);
+                                commands.entity(dialog_ui.0).despawn();
+                            },
+                        );
+
+                    c.spawn((DialogUI(dialog), UiButton, children![Text::new("Cancel")]))
+                        .observe(
+                            |pointer_click: On<Pointer<Click>>,
+                             mut commands: Commands,
+                             q_dialog_ui: Query<&DialogUI>| {
+                                if let Ok(dialog_ui) = q_dialog_ui.get(pointer_click.entity) {
+                                    commands.entity(dialog_ui.0).despawn();
+                                }
+                            },
+                        );
+                });
+            });
+        });
+}
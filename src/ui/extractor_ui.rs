@@ -2,7 +2,11 @@ use bevy::prelude::*;
 
 use crate::{
     buildings::Extractor,
-    ui::{HudWindow, HudWindowParent, InventoryUI, build_building_header},
+    ui::{
+        HudWindow, HudWindowParent, InventoryUI, build_building_condition_ui,
+        build_building_header, build_building_health_ui, build_building_script_ui,
+        build_building_statistics_ui,
+    },
 };
 
 pub fn scan_extractor_ui(mut commands: Commands, q_extractors: Query<Entity, Added<Extractor>>) {
@@ -24,6 +28,10 @@ fn spawn_extractor_ui(
                 HudWindow,
                 children![
                     build_building_header("Element Extractor"),
+                    build_building_health_ui(pointer_click.entity),
+                    build_building_statistics_ui(pointer_click.entity),
+                    build_building_script_ui(pointer_click.entity),
+                    build_building_condition_ui(pointer_click.entity),
                     InventoryUI::new(pointer_click.entity)
                 ],
             ));
@@ -1,6 +1,14 @@
 use bevy::prelude::*;
 
-use crate::{ui::NotificationZone, universe::MainCamera};
+use crate::{
+    ui::NotificationZone,
+    universe::{MainCamera, SHIP_MAX_HULL, Ship},
+};
+
+const STATUS_BAR_WIDTH: f32 = 200.;
+const STATUS_BAR_HEIGHT: f32 = 10.;
+const HULL_COLOR: Color = Color::srgb(0.8, 0.3, 0.2);
+const SHIELD_COLOR: Color = Color::srgb(0.2, 0.6, 1.0);
 
 #[derive(Component)]
 pub struct Hud;
@@ -8,6 +16,12 @@ pub struct Hud;
 #[derive(Component)]
 pub struct HudWindowParent;
 
+#[derive(Component)]
+pub struct HullBar;
+
+#[derive(Component)]
+pub struct ShieldBar;
+
 #[derive(Component)]
 pub struct HudWindowDependent;
 
@@ -56,6 +70,22 @@ pub fn setup_hud(mut commands: Commands, camera: Single<Entity, Added<MainCamera
                 Pickable::IGNORE,
             ));
 
+            c.spawn((
+                Node {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(5.0),
+                    left: Val::Px(5.0),
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(3.0),
+                    ..default()
+                },
+                Pickable::IGNORE,
+                children![
+                    build_status_bar(HullBar, HULL_COLOR),
+                    build_status_bar(ShieldBar, SHIELD_COLOR),
+                ],
+            ));
+
             c.spawn((
                 NotificationZone,
                 Node {
@@ -71,6 +101,47 @@ pub fn setup_hud(mut commands: Commands, camera: Single<Entity, Added<MainCamera
         });
 }
 
+fn build_status_bar(marker: impl Component, color: Color) -> impl Bundle {
+    (
+        Node {
+            width: Val::Px(STATUS_BAR_WIDTH),
+            height: Val::Px(STATUS_BAR_HEIGHT),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.1, 0.1, 0.1, 0.5)),
+        Pickable::IGNORE,
+        children![(
+            marker,
+            Node {
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                ..default()
+            },
+            BackgroundColor(color),
+            Pickable::IGNORE,
+        )],
+    )
+}
+
+pub fn update_status_bars(
+    ship: Single<&Ship, Changed<Ship>>,
+    mut q_hull_bar: Query<&mut Node, (With<HullBar>, Without<ShieldBar>)>,
+    mut q_shield_bar: Query<(&mut Node, &mut Visibility), (With<ShieldBar>, Without<HullBar>)>,
+) {
+    for mut node in &mut q_hull_bar {
+        node.width = Val::Percent(ship.hull() / SHIP_MAX_HULL * 100.);
+    }
+
+    for (mut node, mut visibility) in &mut q_shield_bar {
+        if ship.max_shield() > 0. {
+            node.width = Val::Percent(ship.shield() / ship.max_shield() * 100.);
+            *visibility = Visibility::Inherited;
+        } else {
+            *visibility = Visibility::Hidden;
+        }
+    }
+}
+
 pub fn clear_ui(
     _clear_ui: On<ClearUiEvent>,
     mut commands: Commands,
@@ -4,7 +4,7 @@ use crate::{
     data::{ELEMENTS, ItemId},
     items::{ElementState, Inventory, LogisticProvider, LogisticRequest, LogisticScope},
     ui::{HudWindow, HudWindowParent, UiButton},
-    universe::{SHIP_ACTION_RANGE, Ship},
+    universe::Ship,
 };
 
 #[derive(Component)]
@@ -312,11 +312,11 @@ fn item_transfer_callback(
     from_ship: bool,
 ) -> impl FnMut(
     On<Pointer<Click>>,
-    Single<(&mut Inventory, &GlobalTransform), With<Ship>>,
+    Single<(&Ship, &mut Inventory, &GlobalTransform)>,
     Query<(&mut Inventory, &GlobalTransform), Without<Ship>>,
 ) {
     move |_pointer_click, q_ship, mut q_inventory| {
-        let (mut ship_inventory, ship_transform) = q_ship.into_inner();
+        let (ship, mut ship_inventory, ship_transform) = q_ship.into_inner();
 
         let Ok((mut inventory, transform)) = q_inventory.get_mut(inventory_entity) else {
             return;
@@ -325,7 +325,7 @@ fn item_transfer_callback(
         if ship_transform
             .translation()
             .distance(transform.translation())
-            < SHIP_ACTION_RANGE
+            < ship.action_range()
         {
             if from_ship {
                 ship_inventory.transfer_to(&mut inventory, id, quantity);
@@ -7,9 +7,12 @@ use bevy::{
 };
 
 use crate::{
-    buildings::LogisticFreight,
+    buildings::{LogisticFreight, SelectedFreight},
     items::{LogisticProvider, LogisticScope},
-    ui::{HudWindow, HudWindowDependent, HudWindowParent, InventoryUI, build_building_header},
+    ui::{
+        HudWindow, HudWindowDependent, HudWindowParent, InventoryUI, build_building_header,
+        build_building_health_ui, build_building_script_ui,
+    },
 };
 
 pub fn scan_logistic_freighter(
@@ -42,8 +45,11 @@ fn spawn_cargo_shuttle_ui(
         .with_children(|c| {
             c.spawn((
                 HudWindow,
+                SelectedFreight(pointer_click.entity),
                 children![
                     build_building_header("Cargo Shuttle"),
+                    build_building_health_ui(pointer_click.entity),
+                    build_building_script_ui(pointer_click.entity),
                     InventoryUI::new(pointer_click.entity)
                 ],
             ));
@@ -114,25 +120,28 @@ fn spawn_interplanetary_freighter_ui(
         .entity(*window_parent)
         .despawn_related::<Children>()
         .with_children(|c| {
-            c.spawn(HudWindow).with_children(|c| {
-                c.spawn(build_building_header("Interplanetary Freighter"));
+            c.spawn((HudWindow, SelectedFreight(entity)))
+                .with_children(|c| {
+                    c.spawn(build_building_header("Interplanetary Freighter"));
+                    c.spawn(build_building_health_ui(entity));
+                    c.spawn(build_building_script_ui(entity));
 
-                // Provider minimap
+                    // Provider minimap
 
-                if let Some(image_handle) = image_handle {
-                    c.spawn((
-                        Node {
-                            width: Val::Px(100.0),
-                            height: Val::Px(100.0),
-                            ..default()
-                        },
-                        BackgroundColor(Color::WHITE),
-                        ImageNode::new(image_handle),
-                    ));
-                }
+                    if let Some(image_handle) = image_handle {
+                        c.spawn((
+                            Node {
+                                width: Val::Px(100.0),
+                                height: Val::Px(100.0),
+                                ..default()
+                            },
+                            BackgroundColor(Color::WHITE),
+                            ImageNode::new(image_handle),
+                        ));
+                    }
 
-                // Inventory
-                c.spawn(InventoryUI::new(entity));
-            });
+                    // Inventory
+                    c.spawn(InventoryUI::new(entity));
+                });
         });
 }
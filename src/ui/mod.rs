@@ -1,10 +1,12 @@
-use bevy::prelude::*;
+use bevy::{input::InputSystems, prelude::*};
 
 use crate::SolarSystemSet;
 
+mod alert_ui;
 mod building_ui;
 mod buttons;
 mod crafter_ui;
+mod dialog;
 mod extractor_ui;
 mod hud;
 mod inventory_ui;
@@ -13,10 +15,16 @@ mod notification;
 mod save_load_ui;
 mod ship_ui;
 mod spaceport_ui;
+mod statistics_ui;
+mod text_input;
+mod turret_ui;
+mod wreck_ui;
 
+pub use alert_ui::*;
 pub use building_ui::*;
 pub use buttons::*;
 pub use crafter_ui::*;
+pub use dialog::*;
 pub use extractor_ui::*;
 pub use hud::*;
 pub use inventory_ui::*;
@@ -25,6 +33,10 @@ pub use notification::*;
 pub use save_load_ui::*;
 pub use ship_ui::*;
 pub use spaceport_ui::*;
+pub use statistics_ui::*;
+pub use text_input::*;
+pub use turret_ui::*;
+pub use wreck_ui::*;
 
 pub struct UIPlugin;
 
@@ -34,21 +46,43 @@ impl Plugin for UIPlugin {
             Update,
             (
                 update_ui_buttons,
+                update_text_inputs,
+                update_notifications,
                 (
                     setup_hud,
+                    update_status_bars,
                     clear_ui_or_spawn_ship_ui,
                     spawn_save_ui,
+                    update_journal_toggles,
+                    (
+                        spawn_statistics_ui,
+                        update_statistics_ui,
+                        update_building_statistics_ui,
+                    ),
                     update_inventory_ui.after(clear_ui_or_spawn_ship_ui),
+                    update_ship_modules_ui.after(clear_ui_or_spawn_ship_ui),
                     scan_crafter_ui,
                     scan_extractor_ui,
                     scan_spaceport_ui,
                     scan_logistic_freighter,
-                    update_notifications,
+                    scan_turret_ui,
+                    update_turret_ui,
+                    scan_wreck_ui,
+                    update_building_health_ui,
+                    update_building_script_ui,
+                    update_building_condition_ui,
+                    (
+                        setup_alert_counter.after(setup_hud),
+                        update_alert_counter,
+                        (spawn_alert_icons, update_alert_icons).chain(),
+                    ),
                 )
                     .in_set(SolarSystemSet),
             ),
         )
         .add_observer(clear_ui)
-        .add_observer(observe_notifications);
+        .add_observer(observe_notifications)
+        .add_observer(refresh_save_lists)
+        .add_systems(PreUpdate, consume_keys_while_typing.after(InputSystems));
     }
 }
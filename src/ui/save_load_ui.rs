@@ -1,10 +1,26 @@
+use std::{cmp::Reverse, path::PathBuf, time::UNIX_EPOCH};
+
 use bevy::prelude::*;
 
 use crate::{
-    ui::{HudWindow, HudWindowParent, UiButton},
-    LoadUniverse, SAVES_DIR, SAVE_EXTENSION,
+    LoadUniverse, SAVE_EXTENSION, SAVES_DIR, SaveError, UniverseName, delete_save, duplicate_save,
+    import_save,
+    journal::{EventJournal, journal_path},
+    read_save_header, rename_save, save_path,
+    ui::{HudWindow, HudWindowParent, NotificationEvent, UiButton, spawn_dialog},
 };
 
+#[derive(Component)]
+pub struct SaveList;
+
+// Starts or stops the event journal of the universe
+#[derive(Component)]
+pub struct JournalToggle;
+
+// Rebuilds the save lists after a save file was created, deleted or renamed
+#[derive(Event)]
+pub struct RefreshSaveList;
+
 pub fn spawn_save_ui(
     mut commands: Commands,
     window_parent: Single<Entity, With<HudWindowParent>>,
@@ -16,37 +32,276 @@ pub fn spawn_save_ui(
             .despawn_related::<Children>()
             .with_children(|c| {
                 c.spawn(HudWindow).with_children(|c| {
+                    c.spawn((JournalToggle, UiButton, children![Text::default()]))
+                        .observe(toggle_journal);
+
                     build_load_ui(c);
                 });
             });
     }
 }
 
+fn toggle_journal(
+    _pointer_click: On<Pointer<Click>>,
+    mut commands: Commands,
+    mut journal: ResMut<EventJournal>,
+    universe_name: Res<UniverseName>,
+) {
+    if journal.is_started() {
+        journal.stop();
+        return;
+    }
+
+    let path = journal_path(&universe_name.0);
+
+    if let Err(e) = journal.start(&path) {
+        error!("Can't start journal {}: {e}", path.display());
+        commands.trigger(NotificationEvent(format!("Can't start journal: {e}")));
+    }
+}
+
+pub fn update_journal_toggles(
+    journal: Res<EventJournal>,
+    q_journal_toggles: Query<&Children, With<JournalToggle>>,
+    mut q_texts: Query<&mut Text>,
+) {
+    for children in &q_journal_toggles {
+        let mut texts = q_texts.iter_many_mut(children);
+        while let Some(mut text) = texts.fetch_next() {
+            text.0 = match journal.path() {
+                Some(path) => format!("Stop journal ({})", path.display()),
+                None => "Start journal".to_string(),
+            };
+        }
+    }
+}
+
 pub fn build_load_ui(c: &mut ChildSpawnerCommands) {
-    if let Ok(universes_names) = std::fs::read_dir(format!("assets/{SAVES_DIR}")).map(|dir| {
-        dir.filter_map(|entry| {
-            entry.ok().and_then(|entry| {
-                entry.file_name().into_string().ok().and_then(|file_name| {
-                    if file_name.ends_with(SAVE_EXTENSION) {
-                        Some(file_name.replacen(&format!(".{SAVE_EXTENSION}"), "", 1))
-                    } else {
-                        None
-                    }
+    c.spawn((UiButton, children![Text::new("Import save")]))
+        .observe(
+            |_pointer_click: On<Pointer<Click>>, mut commands: Commands| {
+                spawn_dialog(
+                    &mut commands,
+                    "Path of the save file to import",
+                    Some(String::new()),
+                    |commands, path| {
+                        let result = import_save(&PathBuf::from(&path));
+                        on_save_action(commands, &format!("import {path}"), result);
+                    },
+                );
+            },
+        );
+
+    c.spawn((
+        SaveList,
+        Node {
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(5.0),
+            ..default()
+        },
+    ))
+    .with_children(build_save_list);
+}
+
+pub fn refresh_save_lists(
+    _refresh: On<RefreshSaveList>,
+    mut commands: Commands,
+    q_save_lists: Query<Entity, With<SaveList>>,
+) {
+    for save_list in &q_save_lists {
+        commands
+            .entity(save_list)
+            .despawn_related::<Children>()
+            .with_children(build_save_list);
+    }
+}
+
+fn on_save_action<T>(commands: &mut Commands, action: &str, result: Result<T, SaveError>) {
+    if let Err(e) = result {
+        error!("Can't {action}: {e}");
+        commands.trigger(NotificationEvent(format!("Can't {action}: {e}")));
+    }
+
+    commands.trigger(RefreshSaveList);
+}
+
+fn build_save_list(c: &mut ChildSpawnerCommands) {
+    let Ok(dir) = std::fs::read_dir(format!("assets/{SAVES_DIR}")) else {
+        return;
+    };
+
+    let mut saves = dir
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let file_name = entry.file_name().into_string().ok()?;
+            let universe_name = file_name.strip_suffix(&format!(".{SAVE_EXTENSION}"))?;
+
+            // Saves without metadata are sorted by file modification date
+            let header = read_save_header(&entry.path()).ok();
+            let timestamp = header
+                .as_ref()
+                .map(|header| header.metadata.timestamp)
+                .filter(|timestamp| *timestamp > 0)
+                .or_else(|| {
+                    let modified = entry.metadata().ok()?.modified().ok()?;
+                    Some(modified.duration_since(UNIX_EPOCH).ok()?.as_secs())
                 })
-            })
+                .unwrap_or_default();
+
+            Some((
+                universe_name.to_string(),
+                header.map(|header| header.metadata),
+                timestamp,
+            ))
         })
-        .collect::<Vec<_>>()
-    }) {
-        for universe_name in universes_names {
-            let callback = {
+        .collect::<Vec<_>>();
+
+    // Last played first
+    saves.sort_by_key(|(_, _, timestamp)| Reverse(*timestamp));
+
+    for (universe_name, metadata, timestamp) in saves {
+        let load = {
+            let universe_name = universe_name.clone();
+            move |_pointer_click: On<Pointer<Click>>, mut commands: Commands| {
+                commands.trigger(LoadUniverse(save_path(&universe_name)));
+            }
+        };
+
+        let rename = {
+            let universe_name = universe_name.clone();
+            move |_pointer_click: On<Pointer<Click>>, mut commands: Commands| {
                 let universe_name = universe_name.clone();
-                move |_pointer_click: On<Pointer<Click>>, mut commands: Commands| {
-                    commands.trigger(LoadUniverse(universe_name.clone()));
-                }
-            };
+                spawn_dialog(
+                    &mut commands,
+                    format!("Rename {universe_name}"),
+                    Some(universe_name.clone()),
+                    move |commands, new_name| {
+                        let result = rename_save(&universe_name, &new_name);
+
+                        // The universe being played keeps saving under its new name
+                        if result.is_ok() {
+                            let universe_name = universe_name.clone();
+                            commands.queue(move |world: &mut World| {
+                                if let Some(mut current) = world.get_resource_mut::<UniverseName>()
+                                    && current.0 == universe_name
+                                {
+                                    current.0 = new_name;
+                                }
+                            });
+                        }
+
+                        on_save_action(commands, &format!("rename {universe_name}"), result);
+                    },
+                );
+            }
+        };
 
-            c.spawn((UiButton, children![Text::new(universe_name)]))
-                .observe(callback);
+        let duplicate = {
+            let universe_name = universe_name.clone();
+            move |_pointer_click: On<Pointer<Click>>, mut commands: Commands| {
+                let universe_name = universe_name.clone();
+                spawn_dialog(
+                    &mut commands,
+                    format!("Duplicate {universe_name}?"),
+                    None,
+                    move |commands, _| {
+                        let result = duplicate_save(&universe_name);
+                        on_save_action(commands, &format!("duplicate {universe_name}"), result);
+                    },
+                );
+            }
+        };
+
+        let delete = {
+            let universe_name = universe_name.clone();
+            move |_pointer_click: On<Pointer<Click>>, mut commands: Commands| {
+                let universe_name = universe_name.clone();
+                spawn_dialog(
+                    &mut commands,
+                    format!("Delete {universe_name}? This can't be undone."),
+                    None,
+                    move |commands, _| {
+                        let result = delete_save(&universe_name);
+                        on_save_action(commands, &format!("delete {universe_name}"), result);
+                    },
+                );
+            }
+        };
+
+        let mut details = vec![format!("Last played {}", format_timestamp(timestamp))];
+
+        if let Some(metadata) = metadata.filter(|metadata| metadata.timestamp > 0) {
+            details.push(format!(
+                "In-game {} - Played {}",
+                format_duration(metadata.game_time),
+                format_duration(metadata.play_time),
+            ));
+            details.push(format!(
+                "{} systems visited - {} buildings - Seed {}",
+                metadata.visited_systems, metadata.buildings, metadata.seed,
+            ));
         }
+
+        c.spawn(Node {
+            column_gap: Val::Px(5.0),
+            ..default()
+        })
+        .with_children(|c| {
+            c.spawn((
+                UiButton,
+                children![(
+                    Node {
+                        flex_direction: FlexDirection::Column,
+                        ..default()
+                    },
+                    children![
+                        Text::new(universe_name),
+                        (
+                            Text::new(details.join("\n")),
+                            TextFont {
+                                font_size: 14.0,
+                                ..default()
+                            },
+                        )
+                    ],
+                )],
+            ))
+            .observe(load);
+
+            c.spawn((UiButton, children![Text::new("Rename")]))
+                .observe(rename);
+            c.spawn((UiButton, children![Text::new("Duplicate")]))
+                .observe(duplicate);
+            c.spawn((UiButton, children![Text::new("Delete")]))
+                .observe(delete);
+        });
     }
 }
+
+fn format_duration(seconds: f64) -> String {
+    let minutes = (seconds / 60.) as u64;
+    format!("{}h{:02}", minutes / 60, minutes % 60)
+}
+
+// UTC date, without pulling a date crate
+fn format_timestamp(timestamp: u64) -> String {
+    let days = timestamp / 86400;
+    let seconds = timestamp % 86400;
+
+    // Civil from days, see http://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+
+    format!(
+        "{year}-{month:02}-{day:02} {:02}:{:02} UTC",
+        seconds / 3600,
+        seconds % 3600 / 60
+    )
+}
@@ -2,11 +2,23 @@ use bevy::prelude::*;
 
 use crate::{
     buildings::PlacingBuilding,
-    data::BuildingId,
-    ui::{build_building_ui, ClearUiEvent, HudWindow, HudWindowParent, InventoryUI, UiButton},
-    universe::Ship,
+    data::{BuildingId, ShipModuleId},
+    items::Inventory,
+    ui::{
+        ClearUiEvent, HudWindow, HudWindowParent, InventoryUI, NotificationEvent, UiButton,
+        build_building_ui,
+    },
+    universe::{SHIP_MODULE_SLOTS, Ship, ShipModuleEffect, ShipModules},
 };
 
+#[derive(Component)]
+#[require(Node {
+    flex_direction: FlexDirection::Column,
+    row_gap: Val::Px(5.0),
+    ..default()
+})]
+pub struct ShipModulesUI;
+
 pub fn clear_ui_or_spawn_ship_ui(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
@@ -57,6 +69,8 @@ pub fn clear_ui_or_spawn_ship_ui(
                                 ))
                                 .observe(callback);
                             }
+
+                            c.spawn(ShipModulesUI);
                         });
                     });
                 });
@@ -64,3 +78,107 @@ pub fn clear_ui_or_spawn_ship_ui(
         }
     }
 }
+
+pub fn update_ship_modules_ui(
+    mut commands: Commands,
+    q_ship_modules_ui: Query<(Entity, Ref<ShipModulesUI>)>,
+    ship: Single<(Ref<Ship>, Ref<ShipModules>, Ref<Inventory>)>,
+) {
+    let (ship, modules, inventory) = ship.into_inner();
+
+    for (ui_entity, ui) in &q_ship_modules_ui {
+        if !ui.is_added() && !ship.is_changed() && !modules.is_changed() && !inventory.is_changed()
+        {
+            continue;
+        }
+
+        commands
+            .entity(ui_entity)
+            .despawn_related::<Children>()
+            .with_children(|c| {
+                c.spawn(Text::new(format!(
+                    "Max speed {:.0} - Thrust {:.0} - Cargo {}\n{} - Action range {:.0} - Shield {:.0}",
+                    ship.max_speed(),
+                    ship.thrust(),
+                    inventory.size(),
+                    ship.mining_laser().data().name,
+                    ship.action_range(),
+                    ship.max_shield(),
+                )));
+
+                c.spawn(Text::new(format!(
+                    "Modules ({}/{SHIP_MODULE_SLOTS})",
+                    modules.modules().len()
+                )));
+
+                for (slot, module) in modules.modules().iter().enumerate() {
+                    c.spawn((
+                        UiButton,
+                        children![Text::new(format!(
+                            "Uninstall {}",
+                            module.data().item.data().name
+                        ))],
+                    ))
+                    .observe(uninstall_module_callback(slot));
+                }
+
+                if modules.has_free_slot() {
+                    for module in ShipModuleId::ALL {
+                        let item = module.data().item;
+
+                        if inventory.quantity(item) > 0 {
+                            c.spawn((
+                                UiButton,
+                                children![Text::new(format!("Install {}", item.data().name))],
+                            ))
+                            .observe(install_module_callback(*module));
+                        }
+                    }
+                }
+            });
+    }
+}
+
+fn install_module_callback(
+    module: ShipModuleId,
+) -> impl FnMut(On<Pointer<Click>>, Single<(&mut ShipModules, &mut Inventory)>) {
+    move |_pointer_click, ship| {
+        let (mut modules, mut inventory) = ship.into_inner();
+
+        if modules.has_free_slot() && inventory.try_remove(module.data().item, 1) {
+            modules.install(module);
+        }
+    }
+}
+
+fn uninstall_module_callback(
+    slot: usize,
+) -> impl FnMut(On<Pointer<Click>>, Commands, Single<(&mut ShipModules, &mut Inventory)>) {
+    move |_pointer_click, mut commands, ship| {
+        let (mut modules, mut inventory) = ship.into_inner();
+
+        let Some(module) = modules.modules().get(slot).copied() else {
+            return;
+        };
+
+        let data = module.data();
+
+        // The cargo must still fit without this module, with the module itself
+        let lost_size = match data.effect {
+            ShipModuleEffect::Cargo(size) => size,
+            _ => 0,
+        };
+
+        if inventory.total_quantity() + 1 + lost_size > inventory.size()
+            || !inventory.try_add(data.item, 1)
+        {
+            commands.trigger(NotificationEvent(format!(
+                "Not enough cargo space to uninstall {}",
+                data.item.data().name
+            )));
+            return;
+        }
+
+        modules.uninstall(slot);
+    }
+}
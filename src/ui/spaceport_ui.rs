@@ -2,7 +2,10 @@ use bevy::prelude::*;
 
 use crate::{
     buildings::Spaceport,
-    ui::{HudWindow, HudWindowParent, InventoryUI, build_building_header},
+    ui::{
+        HudWindow, HudWindowParent, InventoryUI, build_building_condition_ui,
+        build_building_header, build_building_health_ui, build_building_script_ui,
+    },
 };
 
 pub fn scan_spaceport_ui(mut commands: Commands, q_extractors: Query<Entity, Added<Spaceport>>) {
@@ -24,6 +27,9 @@ pub fn spawn_spaceport_ui(
                 HudWindow,
                 children![
                     build_building_header("Spaceport"),
+                    build_building_health_ui(pointer_click.entity),
+                    build_building_script_ui(pointer_click.entity),
+                    build_building_condition_ui(pointer_click.entity),
                     InventoryUI::new(pointer_click.entity).with_edit_logistic()
                 ],
             ));
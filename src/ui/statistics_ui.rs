@@ -0,0 +1,290 @@
+use std::{path::PathBuf, time::UNIX_EPOCH};
+
+use bevy::{ecs::spawn::SpawnWith, prelude::*};
+
+use crate::{
+    UniverseName,
+    statistics::{
+        FlowKind, ProductionStatistics, STATISTICS_DIR, StatisticsScope, StatisticsWindow,
+    },
+    ui::{HudWindow, HudWindowParent, NotificationEvent, UiButton, build_building_header},
+    universe::{Ship, SolarSystem},
+};
+
+const GRAPH_MAX_BARS: usize = 60;
+const GRAPH_HEIGHT: f32 = 30.;
+const GRAPH_BAR_WIDTH: f32 = 4.;
+const GRAPH_COLOR: Color = Color::srgb(0.3, 0.8, 0.4);
+
+// Item flows of a scope, rebuilt when a new statistics bucket starts
+#[derive(Component)]
+pub struct StatisticsView {
+    scope: StatisticsScope,
+    window: StatisticsWindow,
+    generation: Option<u32>,
+}
+
+// Last minute production of a building, shown in its HudWindow
+#[derive(Component)]
+pub struct BuildingStatisticsUI(Entity);
+
+pub fn spawn_statistics_ui(
+    mut commands: Commands,
+    window_parent: Single<Entity, With<HudWindowParent>>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::KeyP) {
+        open_statistics_window(&mut commands, *window_parent, StatisticsScope::Global);
+    }
+}
+
+fn open_statistics_window(commands: &mut Commands, window_parent: Entity, scope: StatisticsScope) {
+    commands
+        .entity(window_parent)
+        .despawn_related::<Children>()
+        .with_children(|c| {
+            c.spawn(HudWindow).with_children(|c| {
+                c.spawn(build_building_header("Production statistics"));
+
+                c.spawn(Node {
+                    column_gap: Val::Px(10.0),
+                    margin: UiRect::bottom(Val::Px(10.0)),
+                    ..default()
+                })
+                .with_children(|c| {
+                    c.spawn((UiButton, children![Text::new("Global")]))
+                        .observe(select_scope_callback(Some(StatisticsScope::Global)));
+
+                    c.spawn((UiButton, children![Text::new("Solar system")]))
+                        .observe(select_scope_callback(None));
+
+                    if let StatisticsScope::Building(entity) = scope {
+                        c.spawn((UiButton, children![Text::new("Building")]))
+                            .observe(select_scope_callback(Some(StatisticsScope::Building(
+                                entity,
+                            ))));
+                    }
+
+                    for window in StatisticsWindow::ALL {
+                        c.spawn((UiButton, children![Text::new(window.to_string())]))
+                            .observe(
+                                move |_pointer_click: On<Pointer<Click>>,
+                                      mut view: Single<&mut StatisticsView>| {
+                                    view.window = window;
+                                },
+                            );
+                    }
+
+                    c.spawn((UiButton, children![Text::new("Export CSV")]))
+                        .observe(export_statistics);
+                });
+
+                c.spawn((
+                    StatisticsView {
+                        scope,
+                        window: StatisticsWindow::default(),
+                        generation: None,
+                    },
+                    Node {
+                        flex_direction: FlexDirection::Column,
+                        row_gap: Val::Px(5.0),
+                        overflow: Overflow::scroll_y(),
+                        ..default()
+                    },
+                ));
+            });
+        });
+}
+
+// No scope selects the solar system of the Ship
+fn select_scope_callback(
+    scope: Option<StatisticsScope>,
+) -> impl FnMut(
+    On<Pointer<Click>>,
+    Single<&mut StatisticsView>,
+    Single<Entity, With<Ship>>,
+    Query<&ChildOf>,
+    Query<&SolarSystem>,
+) {
+    move |_pointer_click, mut view, ship, q_parents, q_solar_systems| {
+        let scope = scope.or_else(|| {
+            q_parents
+                .iter_ancestors(*ship)
+                .find_map(|ancestor| q_solar_systems.get(ancestor).ok())
+                .map(|solar_system| StatisticsScope::SolarSystem(solar_system.position))
+        });
+
+        if let Some(scope) = scope {
+            view.scope = scope;
+        }
+    }
+}
+
+fn export_statistics(
+    _pointer_click: On<Pointer<Click>>,
+    mut commands: Commands,
+    statistics: Res<ProductionStatistics>,
+    universe_name: Res<UniverseName>,
+) {
+    let timestamp = UNIX_EPOCH.elapsed().unwrap().as_secs();
+    let path = PathBuf::from(format!(
+        "assets/{STATISTICS_DIR}/{}_{timestamp}.csv",
+        universe_name.0
+    ));
+
+    match statistics.export_csv(&path) {
+        Ok(()) => commands.trigger(NotificationEvent(format!(
+            "Statistics exported to {}",
+            path.display()
+        ))),
+        Err(e) => {
+            error!("Can't export statistics: {e}");
+            commands.trigger(NotificationEvent(format!("Can't export statistics: {e}")));
+        }
+    }
+}
+
+pub fn update_statistics_ui(
+    mut commands: Commands,
+    statistics: Res<ProductionStatistics>,
+    mut q_views: Query<(Entity, &mut StatisticsView)>,
+) {
+    for (entity, mut view) in &mut q_views {
+        if !view.is_changed() && view.generation == Some(statistics.generation()) {
+            continue;
+        }
+
+        view.generation = Some(statistics.generation());
+
+        let rows = statistics
+            .history(view.scope)
+            .map(|history| {
+                history
+                    .items(view.window)
+                    .into_iter()
+                    .map(|item| {
+                        let totals = FlowKind::ALL
+                            .map(|kind| {
+                                format!("{kind} {}", history.total(view.window, kind, item))
+                            })
+                            .join(", ");
+                        let series = history.series(view.window, FlowKind::Produced, item);
+                        (item, totals, series)
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        let title = format!("{} over {}", view.scope, view.window);
+
+        commands
+            .entity(entity)
+            .despawn_related::<Children>()
+            .with_children(|c| {
+                c.spawn(Text::new(title));
+
+                if rows.is_empty() {
+                    c.spawn(Text::new("Nothing produced yet"));
+                }
+
+                for (item, totals, series) in rows {
+                    c.spawn(Node {
+                        align_items: AlignItems::End,
+                        column_gap: Val::Px(10.0),
+                        ..default()
+                    })
+                    .with_children(|c| {
+                        c.spawn((
+                            Text::new(item.data().name),
+                            Node {
+                                width: Val::Px(150.0),
+                                ..default()
+                            },
+                        ));
+                        c.spawn(build_graph(&series));
+                        c.spawn(Text::new(totals));
+                    });
+                }
+            });
+    }
+}
+
+// Bars of the produced quantities, oldest on the left
+fn build_graph(series: &[u32]) -> impl Bundle {
+    let chunk_size = series.len().div_ceil(GRAPH_MAX_BARS).max(1);
+    let bars = series
+        .chunks(chunk_size)
+        .map(|chunk| chunk.iter().sum::<u32>())
+        .collect::<Vec<_>>();
+    let max = bars.iter().copied().max().unwrap_or_default().max(1);
+
+    (
+        Node {
+            height: Val::Px(GRAPH_HEIGHT),
+            align_items: AlignItems::End,
+            column_gap: Val::Px(1.0),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.3)),
+        Children::spawn(SpawnWith(move |c: &mut ChildSpawner| {
+            for bar in bars {
+                c.spawn((
+                    Node {
+                        width: Val::Px(GRAPH_BAR_WIDTH),
+                        height: Val::Percent(bar as f32 / max as f32 * 100.),
+                        ..default()
+                    },
+                    BackgroundColor(GRAPH_COLOR),
+                ));
+            }
+        })),
+    )
+}
+
+pub fn build_building_statistics_ui(entity: Entity) -> impl Bundle {
+    (
+        Node {
+            align_items: AlignItems::Center,
+            flex_direction: FlexDirection::Row,
+            column_gap: Val::Px(10.0),
+            margin: UiRect::bottom(Val::Px(10.0)),
+            ..default()
+        },
+        Children::spawn(SpawnWith(move |c: &mut ChildSpawner| {
+            c.spawn((BuildingStatisticsUI(entity), Text::default()));
+
+            c.spawn((UiButton, children![Text::new("Statistics")]))
+                .observe(
+                move |_pointer_click: On<Pointer<Click>>,
+                      mut commands: Commands,
+                      window_parent: Single<Entity, With<HudWindowParent>>| {
+                    open_statistics_window(
+                        &mut commands,
+                        *window_parent,
+                        StatisticsScope::Building(entity),
+                    );
+                },
+            );
+        })),
+    )
+}
+
+pub fn update_building_statistics_ui(
+    mut q_building_statistics_ui: Query<(&BuildingStatisticsUI, &mut Text)>,
+    statistics: Res<ProductionStatistics>,
+) {
+    for (building_statistics_ui, mut text) in &mut q_building_statistics_ui {
+        let history = statistics.history(StatisticsScope::Building(building_statistics_ui.0));
+
+        let [produced, consumed] = [FlowKind::Produced, FlowKind::Consumed].map(|kind| {
+            history.map_or(0, |history| {
+                history
+                    .totals(StatisticsWindow::OneMinute, kind)
+                    .values()
+                    .sum::<u32>()
+            })
+        });
+
+        text.0 = format!("Last minute: {produced} produced, {consumed} consumed");
+    }
+}
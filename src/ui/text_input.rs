@@ -0,0 +1,59 @@
+use bevy::{
+    input::{
+        ButtonState,
+        keyboard::{Key, KeyboardInput},
+    },
+    prelude::*,
+};
+
+// Single line text field, receives all keyboard input while it exists
+#[derive(Component, Default)]
+#[require(
+    Text,
+    Node {
+        min_width: Val::Px(300.0),
+        padding: UiRect::all(Val::Px(5.0)),
+        ..default()
+    },
+    BackgroundColor(Color::srgb(0.05, 0.05, 0.05))
+)]
+pub struct TextInput(pub String);
+
+pub fn update_text_inputs(
+    mut keyboard_inputs: MessageReader<KeyboardInput>,
+    mut q_text_inputs: Query<(&mut TextInput, &mut Text)>,
+) {
+    let keyboard_inputs = keyboard_inputs
+        .read()
+        .filter(|input| input.state == ButtonState::Pressed)
+        .collect::<Vec<_>>();
+
+    for (mut text_input, mut text) in &mut q_text_inputs {
+        if keyboard_inputs.is_empty() && !text_input.is_changed() {
+            continue;
+        }
+
+        for input in &keyboard_inputs {
+            match &input.logical_key {
+                Key::Backspace => {
+                    text_input.0.pop();
+                }
+                Key::Space => text_input.0.push(' '),
+                Key::Character(characters) => text_input.0.push_str(characters),
+                _ => {}
+            }
+        }
+
+        text.0 = format!("{}_", text_input.0);
+    }
+}
+
+// Typing in a text field must not trigger the game hotkeys
+pub fn consume_keys_while_typing(
+    q_text_inputs: Query<(), With<TextInput>>,
+    mut keyboard_input: ResMut<ButtonInput<KeyCode>>,
+) {
+    if !q_text_inputs.is_empty() {
+        keyboard_input.reset_all();
+    }
+}
@@ -0,0 +1,58 @@
+use bevy::prelude::*;
+
+use crate::{
+    buildings::Turret,
+    data::ItemId,
+    items::Inventory,
+    ui::{
+        HudWindow, HudWindowParent, InventoryUI, build_building_header, build_building_health_ui,
+        build_building_script_ui,
+    },
+};
+
+// Ammo and kills of the turret shown in the HudWindow
+#[derive(Component)]
+pub struct TurretStatsUI(Entity);
+
+pub fn scan_turret_ui(mut commands: Commands, q_turrets: Query<Entity, Added<Turret>>) {
+    for entity in &q_turrets {
+        commands.entity(entity).observe(spawn_turret_ui);
+    }
+}
+
+fn spawn_turret_ui(
+    pointer_click: On<Pointer<Click>>,
+    mut commands: Commands,
+    window_parent: Single<Entity, With<HudWindowParent>>,
+) {
+    commands
+        .entity(*window_parent)
+        .despawn_related::<Children>()
+        .with_children(|c| {
+            c.spawn((
+                HudWindow,
+                children![
+                    build_building_header("Turret"),
+                    build_building_health_ui(pointer_click.entity),
+                    build_building_script_ui(pointer_click.entity),
+                    (TurretStatsUI(pointer_click.entity), Text::default()),
+                    InventoryUI::new(pointer_click.entity)
+                ],
+            ));
+        });
+}
+
+pub fn update_turret_ui(
+    mut q_turret_stats_ui: Query<(&TurretStatsUI, &mut Text)>,
+    q_turrets: Query<(&Turret, &Inventory)>,
+) {
+    for (turret_stats_ui, mut text) in &mut q_turret_stats_ui {
+        if let Ok((turret, inventory)) = q_turrets.get(turret_stats_ui.0) {
+            text.0 = format!(
+                "Ammo: {}\nKills: {}",
+                inventory.quantity(ItemId::TurretAmmo),
+                turret.kills()
+            );
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use bevy::prelude::*;
+
+use crate::{
+    buildings::Wreck,
+    ui::{HudWindow, HudWindowParent, InventoryUI, build_building_header},
+};
+
+pub fn scan_wreck_ui(mut commands: Commands, q_wrecks: Query<Entity, Added<Wreck>>) {
+    for entity in &q_wrecks {
+        commands.entity(entity).observe(spawn_wreck_ui);
+    }
+}
+
+fn spawn_wreck_ui(
+    pointer_click: On<Pointer<Click>>,
+    mut commands: Commands,
+    window_parent: Single<Entity, With<HudWindowParent>>,
+) {
+    commands
+        .entity(*window_parent)
+        .despawn_related::<Children>()
+        .with_children(|c| {
+            c.spawn((
+                HudWindow,
+                children![
+                    build_building_header("Wreck"),
+                    InventoryUI::new(pointer_click.entity)
+                ],
+            ));
+        });
+}
@@ -1,6 +1,9 @@
 use bevy::prelude::*;
 
-use crate::{items::Inventory, universe::update_ship_mining};
+use crate::{
+    items::Inventory,
+    universe::{aim_ship_mining, start_ship_mining},
+};
 
 #[derive(Component, Reflect, Default)]
 #[reflect(Component, Default)]
@@ -39,6 +42,9 @@ impl Astre {
 
 pub fn scan_astres(mut commands: Commands, query: Query<Entity, Added<Astre>>) {
     for entity in &query {
-        commands.entity(entity).observe(update_ship_mining);
+        commands
+            .entity(entity)
+            .observe(start_ship_mining)
+            .observe(aim_ship_mining);
     }
 }
@@ -0,0 +1,140 @@
+use bevy::prelude::*;
+
+use crate::{
+    buildings::{BuildingHighlight, LogisticFreight, PlacingBuilding},
+    ui::NotificationEvent,
+    universe::{Astre, Ship},
+};
+
+// The Ship stops well inside its action range, which grows with the installed scanners
+const AUTOPILOT_STOP_RATIO: f32 = 0.5;
+
+// Relative speed under which the Ship is considered stopped next to the target
+const AUTOPILOT_ARRIVAL_SPEED: f32 = 20.;
+
+// Caps how far ahead of a moving target the Ship aims
+const AUTOPILOT_MAX_LEAD_TIME: f32 = 30.;
+
+// Only part of the thrust is used to plan braking, the rest absorbs the target's movement
+const AUTOPILOT_BRAKING_RATIO: f32 = 0.5;
+
+// Flies the Ship to an astre, building or freighter. Removed on arrival or on manual input.
+#[derive(Component)]
+pub struct Autopilot {
+    target: Entity,
+    frame: Entity, // parent of the Ship the positions are relative to
+    last_target_position: Option<Vec2>, // to estimate the target velocity
+}
+
+impl Autopilot {
+    pub fn new(target: Entity) -> Self {
+        Self {
+            target,
+            frame: Entity::PLACEHOLDER,
+            last_target_position: None,
+        }
+    }
+}
+
+// Right click on an astre, a building or a freighter to fly there
+pub fn set_autopilot_target(
+    mut pointer_press: On<Pointer<Press>>,
+    mut commands: Commands,
+    placing_building: Option<Res<PlacingBuilding>>,
+    ship: Single<Entity, With<Ship>>,
+    q_targets: Query<
+        Option<&Name>,
+        Or<(With<Astre>, With<BuildingHighlight>, With<LogisticFreight>)>,
+    >,
+) {
+    if placing_building.is_some() || pointer_press.button != PointerButton::Secondary {
+        return;
+    }
+
+    let Ok(name) = q_targets.get(pointer_press.entity) else {
+        return;
+    };
+
+    // Buildings are children of astres: don't retarget to the astre when the event bubbles up
+    pointer_press.propagate(false);
+
+    commands
+        .entity(*ship)
+        .insert(Autopilot::new(pointer_press.entity));
+
+    commands.trigger(NotificationEvent(format!(
+        "Autopilot engaged: {}",
+        name.map_or("target", Name::as_str)
+    )));
+}
+
+pub fn update_autopilot(
+    mut commands: Commands,
+    time: Res<Time>,
+    q_ship: Single<(Entity, &mut Ship, &mut Autopilot, &Transform, &ChildOf)>,
+    q_global_transforms: Query<&GlobalTransform>,
+) {
+    let (ship_entity, mut ship, mut autopilot, transform, child_of) = q_ship.into_inner();
+
+    let delta = time.delta_secs();
+
+    if delta == 0. {
+        return;
+    }
+
+    let (Ok(target_global_transform), Ok(frame_global_transform)) = (
+        q_global_transforms.get(autopilot.target),
+        q_global_transforms.get(child_of.parent()),
+    ) else {
+        commands.entity(ship_entity).remove::<Autopilot>();
+        commands.trigger(NotificationEvent("Autopilot target lost".to_string()));
+        return;
+    };
+
+    // Work in the frame of the Ship's parent, which is what Ship::speed moves the Ship in
+    let target_position = target_global_transform
+        .reparented_to(frame_global_transform)
+        .translation
+        .truncate();
+
+    if autopilot.frame != child_of.parent() {
+        autopilot.frame = child_of.parent();
+        autopilot.last_target_position = None;
+    }
+
+    let target_velocity = autopilot
+        .last_target_position
+        .map_or(Vec2::ZERO, |last| (target_position - last) / delta);
+    autopilot.last_target_position = Some(target_position);
+
+    let ship_position = transform.translation.truncate();
+    let distance = ship_position.distance(target_position);
+    let relative_speed = ship.speed() - target_velocity;
+    let stop_distance = ship.action_range() * AUTOPILOT_STOP_RATIO;
+
+    if distance < stop_distance && relative_speed.length() < AUTOPILOT_ARRIVAL_SPEED {
+        commands.entity(ship_entity).remove::<Autopilot>();
+        commands.trigger(NotificationEvent("Autopilot arrived".to_string()));
+        return;
+    }
+
+    // Lead orbiting targets: aim where the target will be when the Ship gets there
+    let closing_speed = relative_speed.length().max(AUTOPILOT_ARRIVAL_SPEED);
+    let lead_time = (distance / closing_speed).min(AUTOPILOT_MAX_LEAD_TIME);
+    let aim = target_position + target_velocity * lead_time;
+
+    // Fastest approach speed that still lets the Ship brake before the stop distance
+    let braking = ship.thrust() * AUTOPILOT_BRAKING_RATIO;
+    let remaining = (aim.distance(ship_position) - stop_distance / 2.).max(0.);
+    let approach_speed = (2. * braking * remaining).sqrt();
+
+    let desired_speed =
+        target_velocity + (aim - ship_position).normalize_or_zero() * approach_speed;
+
+    if ship.speed().length() > desired_speed.length() + ship.thrust() * delta {
+        ship.brake();
+    }
+
+    let movement = (desired_speed - ship.speed()) / (ship.thrust() * delta);
+    ship.accelerate(movement.clamp_length_max(1.), delta);
+}
@@ -104,6 +104,29 @@ pub fn update_camera(
         * 1.5;
 }
 
+// Pans the camera, in pan mode, to center the view on a world position
+pub fn focus_camera_on(
+    projection: &mut Projection,
+    camera_transform: &GlobalTransform,
+    viewport_size: Vec2,
+    target: Vec2,
+) {
+    let Projection::Orthographic(projection) = projection else {
+        return;
+    };
+
+    // Below this scale, update_camera brings the viewport back to the Ship
+    projection.scale = projection.scale.max(SWITCH_TO_PAN_MODE * 2.);
+
+    let offset = camera_transform
+        .affine()
+        .inverse()
+        .transform_point3(target.extend(0.))
+        .truncate();
+
+    projection.viewport_origin = Vec2::splat(0.5) - offset / (viewport_size * projection.scale);
+}
+
 pub fn reset_camera_viewport(q_projection: Single<&mut Projection, With<MainCamera>>) {
     let mut projection = q_projection.into_inner();
     let Projection::Orthographic(projection) = projection.as_mut() else {
@@ -55,17 +55,7 @@ pub fn update_dockable_on_astre(
                 - astre_global_transform.translation().truncate();
             let distance = distance.length();
 
-            let can_dock = match dockable.location {
-                LocationOnAstre::Surface => distance < astre.surface_radius(),
-                LocationOnAstre::Atmosphere => {
-                    distance < astre.atmosphere_radius() && distance > astre.surface_radius()
-                }
-                LocationOnAstre::SurfaceOrAtmosphere => distance < astre.atmosphere_radius(),
-                LocationOnAstre::CloseOrbit => {
-                    distance < astre.close_orbit_radius() && distance > astre.atmosphere_radius()
-                }
-                LocationOnAstre::Anywhere => distance < astre.close_orbit_radius(),
-            };
+            let can_dock = dockable.location.allows(astre, distance);
 
             if can_dock {
                 let astre_global_z = astre_global_transform.translation().z;
@@ -0,0 +1,398 @@
+use bevy::{
+    ecs::entity::EntityHashMap,
+    platform::collections::HashMap,
+    prelude::*,
+    scene::{ron, serde::SceneDeserializer},
+};
+use serde::de::DeserializeSeed;
+
+use crate::{
+    buildings::{BuildingHighlight, Wreck, repair_logistic_references},
+    items::Inventory,
+    save_scene_builder,
+    universe::{
+        ActiveSolarSystem, Astre, Loot, Ship, SolarSystem, Worm, build_solar_system,
+        rebuild_worm_segments,
+    },
+};
+
+// Inactive solar systems are compacted after this delay
+const UNLOAD_AFTER: f32 = 120.0;
+
+// Index of an entity in the generation order of its solar system.
+// Regenerating a solar system from its seed gives the same entities the same ids.
+#[derive(Component, Reflect, Default, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[reflect(Component, Default)]
+pub struct ProceduralId(pub u32);
+
+#[derive(Reflect, Default, Debug)]
+pub struct ProceduralDelta {
+    id: u32,
+    transform: Option<Transform>,
+    inventory: Option<Inventory>,
+    worm: Option<Worm>,
+}
+
+// Compact state of a solar system whose descendants have been despawned.
+// Procedural content is regenerated from SolarSystem::seed(), then the deltas and the player-made entities are applied.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component, Default)]
+pub struct DormantSolarSystem {
+    deltas: Vec<ProceduralDelta>,
+    removed: Vec<u32>,
+    buildings: String,           // DynamicScene RON of the player-made entities
+    anchors: Vec<(Entity, u32)>, // (entity in the buildings scene, ProceduralId of the regenerated entity)
+    root: Entity,                // solar system entity in the buildings scene
+    nb_buildings: usize,
+}
+
+impl DormantSolarSystem {
+    pub fn nb_buildings(&self) -> usize {
+        self.nb_buildings
+    }
+}
+
+impl Default for DormantSolarSystem {
+    fn default() -> Self {
+        Self {
+            deltas: vec![],
+            removed: vec![],
+            buildings: String::new(),
+            anchors: vec![],
+            root: Entity::PLACEHOLDER,
+            nb_buildings: 0,
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct SolarSystemIdle(Timer);
+
+impl Default for SolarSystemIdle {
+    fn default() -> Self {
+        Self(Timer::from_seconds(UNLOAD_AFTER, TimerMode::Once))
+    }
+}
+
+// Numbers the procedural entities of a freshly generated solar system, in depth-first Children order
+pub fn assign_procedural_ids(entity: EntityWorldMut) {
+    let root = entity.id();
+    let world = entity.into_world_mut();
+
+    let mut next_id = 0;
+    let mut stack = vec![root];
+
+    while let Some(entity) = stack.pop() {
+        let entity_ref = world.entity(entity);
+
+        if entity_ref.contains::<Astre>() || entity_ref.contains::<Worm>() {
+            world.entity_mut(entity).insert(ProceduralId(next_id));
+            next_id += 1;
+        }
+
+        if let Some(children) = world.get::<Children>(entity) {
+            stack.extend(children.iter().rev());
+        }
+    }
+}
+
+pub fn procedural_entities(world: &mut World, root: Entity) -> HashMap<u32, Entity> {
+    let mut query = world.query::<(Entity, &ProceduralId)>();
+
+    descendants(world, root)
+        .into_iter()
+        .filter_map(|entity| query.get(world, entity).ok())
+        .map(|(entity, id)| (id.0, entity))
+        .collect()
+}
+
+fn descendants(world: &World, root: Entity) -> Vec<Entity> {
+    let mut entities = vec![];
+    let mut stack = vec![root];
+
+    while let Some(entity) = stack.pop() {
+        if let Some(children) = world.get::<Children>(entity) {
+            entities.extend(children.iter());
+            stack.extend(children.iter());
+        }
+    }
+
+    entities
+}
+
+// Returns None if the solar system can't be compacted (legacy entities without ProceduralId)
+pub fn snapshot_solar_system(world: &mut World, root: Entity) -> Option<DormantSolarSystem> {
+    let position = world.get::<SolarSystem>(root)?.position;
+
+    let descendants = descendants(world, root);
+
+    let live = procedural_entities(world, root);
+
+    if live.is_empty() {
+        return None;
+    }
+
+    // Regenerate the solar system in a scratch world to diff against it
+    let mut scratch = World::new();
+    let scratch_root = scratch.spawn(build_solar_system(position)).id();
+    assign_procedural_ids(scratch.entity_mut(scratch_root));
+    let generated = procedural_entities(&mut scratch, scratch_root);
+
+    let mut deltas = vec![];
+    let mut removed = vec![];
+
+    for (id, generated_entity) in &generated {
+        let Some(entity) = live.get(id) else {
+            removed.push(*id);
+            continue;
+        };
+
+        let transform = world
+            .get::<Transform>(*entity)
+            .filter(|t| Some(*t) != scratch.get::<Transform>(*generated_entity))
+            .copied();
+
+        let inventory = world
+            .get::<Inventory>(*entity)
+            .filter(|i| Some(*i) != scratch.get::<Inventory>(*generated_entity))
+            .cloned();
+
+        let worm = world
+            .get::<Worm>(*entity)
+            .filter(|w| Some(*w) != scratch.get::<Worm>(*generated_entity))
+            .cloned();
+
+        if transform.is_some() || inventory.is_some() || worm.is_some() {
+            deltas.push(ProceduralDelta {
+                id: *id,
+                transform,
+                inventory,
+                worm,
+            });
+        }
+    }
+
+    // Player-made entities, anchored to their regenerated parents
+    let buildings = descendants
+        .iter()
+        .copied()
+        .filter(|e| {
+            let entity = world.entity(*e);
+            entity.contains::<BuildingHighlight>() || entity.contains::<Wreck>()
+        })
+        .collect::<Vec<_>>();
+
+    let nb_buildings = buildings.len();
+
+    // Items dropped in space are kept too, until they are collected
+    let loots = descendants
+        .iter()
+        .copied()
+        .filter(|e| world.entity(*e).contains::<Loot>())
+        .collect::<Vec<_>>();
+
+    // Worms born in nests aren't regenerated: they are saved with their segments, like buildings
+    let hatchlings = descendants
+        .iter()
+        .copied()
+        .filter(|e| {
+            let entity = world.entity(*e);
+            entity.contains::<Worm>() && !entity.contains::<ProceduralId>()
+        })
+        .collect::<Vec<_>>();
+
+    let hatchling_segments = hatchlings
+        .iter()
+        .filter_map(|e| world.get::<Children>(*e))
+        .flatten()
+        .copied()
+        .collect::<Vec<_>>();
+
+    let anchors = live.iter().map(|(id, entity)| (*entity, *id)).collect();
+
+    let scene = save_scene_builder(world)
+        .deny_component::<Children>() // rebuilt from ChildOf when rehydrating
+        .extract_entities(
+            buildings
+                .into_iter()
+                .chain(loots)
+                .chain(hatchlings)
+                .chain(hatchling_segments),
+        )
+        .build();
+
+    let type_registry = world.resource::<AppTypeRegistry>().read();
+
+    let buildings = match scene.serialize(&type_registry) {
+        Ok(serialized) => serialized,
+        Err(e) => {
+            error!("Error while serializing the buildings of solar system {position:?}: {e:?}");
+            return None;
+        }
+    };
+
+    Some(DormantSolarSystem {
+        deltas,
+        removed,
+        buildings,
+        anchors,
+        root,
+        nb_buildings,
+    })
+}
+
+pub struct UnloadSolarSystem(pub Entity);
+
+impl Command for UnloadSolarSystem {
+    fn apply(self, world: &mut World) {
+        if descendants(world, self.0)
+            .iter()
+            .any(|e| world.entity(*e).contains::<Ship>())
+        {
+            return;
+        }
+
+        let Some(dormant) = snapshot_solar_system(world, self.0) else {
+            return;
+        };
+
+        debug!(
+            "Unloading solar system {:?}: {} deltas, {} removed",
+            self.0,
+            dormant.deltas.len(),
+            dormant.removed.len()
+        );
+
+        world
+            .entity_mut(self.0)
+            .despawn_related::<Children>()
+            .insert(dormant);
+    }
+}
+
+pub fn rehydrate_solar_system(mut entity: EntityWorldMut) {
+    let Some(dormant) = entity.take::<DormantSolarSystem>() else {
+        return;
+    };
+
+    let Some(position) = entity.get::<SolarSystem>().map(|s| s.position) else {
+        return;
+    };
+
+    let root = entity.id();
+
+    entity.insert(build_solar_system(position));
+
+    let world = entity.into_world_mut();
+    assign_procedural_ids(world.entity_mut(root));
+    let generated = procedural_entities(world, root);
+
+    for delta in dormant.deltas {
+        let Some(entity) = generated.get(&delta.id) else {
+            continue;
+        };
+
+        let mut entity = world.entity_mut(*entity);
+
+        if let Some(transform) = delta.transform {
+            entity.insert(transform);
+        }
+
+        if let Some(inventory) = delta.inventory {
+            entity.insert(inventory);
+        }
+
+        // Worms may have grown or starved since they were generated
+        if let Some(worm) = delta.worm {
+            entity.insert(worm);
+            rebuild_worm_segments(entity);
+        }
+    }
+
+    for id in dormant.removed {
+        if let Some(entity) = generated.get(&id) {
+            world.entity_mut(*entity).despawn();
+        }
+    }
+
+    // Player-made entities
+    let scene = {
+        let type_registry = world.resource::<AppTypeRegistry>().read();
+        let scene_deserializer = SceneDeserializer {
+            type_registry: &type_registry,
+        };
+
+        ron::de::Deserializer::from_str(&dormant.buildings)
+            .map_err(|e| e.to_string())
+            .and_then(|mut deserializer| {
+                scene_deserializer
+                    .deserialize(&mut deserializer)
+                    .map_err(|e| e.to_string())
+            })
+    };
+
+    let scene = match scene {
+        Ok(scene) => scene,
+        Err(e) => {
+            error!("Error while deserializing the buildings of solar system {position:?}: {e}");
+            return;
+        }
+    };
+
+    let mut entity_map = EntityHashMap::default();
+    entity_map.insert(dormant.root, root);
+    for (scene_entity, id) in dormant.anchors {
+        if let Some(entity) = generated.get(&id) {
+            entity_map.insert(scene_entity, *entity);
+        }
+    }
+
+    if let Err(e) = scene.write_to_world(world, &mut entity_map) {
+        error!("Error while spawning the buildings of solar system {position:?}: {e:?}");
+        return;
+    }
+
+    // Scenes skip relationship hooks: insert ChildOf again so the parents' Children are updated
+    for scene_entity in &scene.entities {
+        let entity = entity_map[&scene_entity.entity];
+        if let Some(parent) = world.get::<ChildOf>(entity).map(ChildOf::parent) {
+            world.entity_mut(entity).insert(ChildOf(parent));
+        }
+    }
+
+    repair_logistic_references(world);
+}
+
+pub fn unload_idle_solar_systems(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut q_solar_systems: Query<
+        (Entity, &mut SolarSystemIdle, Has<ActiveSolarSystem>),
+        (With<SolarSystem>, Without<DormantSolarSystem>),
+    >,
+) {
+    for (entity, mut idle, active) in &mut q_solar_systems {
+        if active {
+            idle.0.reset();
+        } else if idle.0.tick(time.delta()).just_finished() {
+            commands.queue(UnloadSolarSystem(entity));
+        }
+    }
+}
+
+// Rehydrates the solar system the Ship arrives in, after travelling or loading a save
+pub fn rehydrate_ship_solar_system(
+    mut commands: Commands,
+    ship: Single<(Entity, &ChildOf), With<Ship>>,
+    q_dormant: Query<(), With<DormantSolarSystem>>,
+) {
+    let (ship_entity, child_of) = *ship;
+    let solar_system = child_of.parent();
+
+    if q_dormant.contains(solar_system) {
+        commands.entity(solar_system).queue(rehydrate_solar_system);
+
+        // Saves don't keep the Children of solar systems: link the Ship again
+        commands.entity(ship_entity).insert(ChildOf(solar_system));
+    }
+}
@@ -0,0 +1,252 @@
+use bevy::prelude::*;
+
+use crate::{
+    data::ELEMENTS,
+    items::{ElementState, Inventory},
+    ui::NotificationEvent,
+    universe::{Astre, Autopilot, DockableOnAstre, Ship, ship_movement_input},
+};
+
+// Surface gravity of an astre is GRAVITATIONAL_CONSTANT * density * radius
+const GRAVITATIONAL_CONSTANT: f32 = 0.1;
+
+// Ratio of the Ship's thrust used to circularize its orbit
+const ORBIT_ASSIST_RATIO: f32 = 0.3;
+
+const TRAJECTORY_PREDICTION_STEPS: usize = 300;
+const TRAJECTORY_PREDICTION_STEP: f32 = 0.1; // seconds
+const TRAJECTORY_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.4);
+const TRAJECTORY_ORBIT_COLOR: Color = Color::srgba(0.3, 1.0, 0.3, 0.6);
+const TRAJECTORY_IMPACT_COLOR: Color = Color::srgba(1.0, 0.3, 0.3, 0.6);
+
+#[derive(Resource, Default, PartialEq, Eq, Clone, Copy, Debug)]
+pub enum FlightModel {
+    #[default]
+    Arcade,
+    Newtonian, // astres pull the Ship, no drag
+}
+
+fn element_density(state: &ElementState) -> f32 {
+    match state {
+        ElementState::Solid => 1.0,
+        ElementState::Liquid => 0.8,
+        ElementState::Gas | ElementState::Plasma => 0.5,
+    }
+}
+
+// Mass from the volume of the astre and the average density of its composition
+pub fn astre_mass(astre: &Astre, inventory: &Inventory) -> f32 {
+    let (total_density, total_quantity) = inventory
+        .items()
+        .iter()
+        .filter_map(|(id, quantity)| {
+            ELEMENTS
+                .get(id)
+                .map(|element| (element_density(&element.state), *quantity as f32))
+        })
+        .fold((0., 0.), |(density, total), (d, quantity)| {
+            (density + d * quantity, total + quantity)
+        });
+
+    let density = if total_quantity > 0. {
+        total_density / total_quantity
+    } else {
+        1.0
+    };
+
+    density * astre.atmosphere_radius().powi(3)
+}
+
+// Astre as seen by the gravity simulation, in the frame of the Ship's parent
+pub struct GravityBody {
+    position: Vec2,
+    radius: f32,
+    mass: f32,
+}
+
+impl GravityBody {
+    // Linear inside the astre, inverse square outside
+    fn acceleration_at(&self, position: Vec2) -> Vec2 {
+        let offset = self.position - position;
+        let distance = offset.length();
+
+        let magnitude = if distance < self.radius {
+            GRAVITATIONAL_CONSTANT * self.mass * distance / self.radius.powi(3)
+        } else {
+            GRAVITATIONAL_CONSTANT * self.mass / distance.powi(2)
+        };
+
+        offset.normalize_or_zero() * magnitude
+    }
+}
+
+// When docked, the Ship moves with its astre, which is on rails: only the tidal part of the other astres' pull applies
+fn gravity_at(bodies: &[GravityBody], position: Vec2, docked: bool) -> Vec2 {
+    let gravity = bodies.iter().map(|b| b.acceleration_at(position)).sum();
+
+    if docked {
+        gravity
+            - bodies
+                .iter()
+                .map(|b| b.acceleration_at(Vec2::ZERO))
+                .sum::<Vec2>()
+    } else {
+        gravity
+    }
+}
+
+fn gravity_bodies<'a>(
+    frame_global_transform: &GlobalTransform,
+    q_astres: impl Iterator<Item = (&'a Astre, &'a Inventory, &'a GlobalTransform)>,
+) -> Vec<GravityBody> {
+    let to_frame = frame_global_transform.affine().inverse();
+
+    q_astres
+        .map(|(astre, inventory, global_transform)| GravityBody {
+            position: to_frame
+                .transform_point3(global_transform.translation())
+                .truncate(),
+            radius: astre.surface_radius(),
+            mass: astre_mass(astre, inventory),
+        })
+        .collect()
+}
+
+pub fn toggle_flight_model(mut commands: Commands, mut flight_model: ResMut<FlightModel>) {
+    *flight_model = match *flight_model {
+        FlightModel::Arcade => FlightModel::Newtonian,
+        FlightModel::Newtonian => FlightModel::Arcade,
+    };
+
+    commands.trigger(NotificationEvent(format!(
+        "Flight model: {:?}",
+        *flight_model
+    )));
+}
+
+pub fn apply_gravity(
+    time: Res<Time>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    flight_model: Res<FlightModel>,
+    q_ship: Single<(
+        &mut Ship,
+        &Transform,
+        &ChildOf,
+        &DockableOnAstre,
+        Has<Autopilot>,
+    )>,
+    q_global_transforms: Query<&GlobalTransform>,
+    q_astres: Query<(&Astre, &Inventory, &GlobalTransform, &InheritedVisibility), Without<Ship>>,
+) {
+    if *flight_model != FlightModel::Newtonian {
+        return;
+    }
+
+    let (mut ship, transform, child_of, dockable, autopilot) = q_ship.into_inner();
+
+    let Ok(frame_global_transform) = q_global_transforms.get(child_of.parent()) else {
+        return;
+    };
+
+    let bodies = gravity_bodies(
+        frame_global_transform,
+        q_astres
+            .iter()
+            .filter(|(_, _, _, v)| v.get())
+            .map(|(a, i, g, _)| (a, i, g)),
+    );
+
+    let position = transform.translation.truncate();
+    let delta = time.delta_secs();
+
+    ship.apply_acceleration(gravity_at(&bodies, position, dockable.on_astre), delta);
+
+    // Orbital insertion assist: when coasting in the close orbit of an astre, circularize the orbit
+    let coasting = ship_movement_input(&keyboard_input) == Vec2::ZERO
+        && !keyboard_input.pressed(KeyCode::Space)
+        && !autopilot;
+
+    if coasting
+        && dockable.on_astre
+        && let Ok((astre, inventory, _, _)) = q_astres.get(child_of.parent())
+        && position.length() > astre.surface_radius()
+    {
+        let radius = position.length();
+        let orbital_speed = (GRAVITATIONAL_CONSTANT * astre_mass(astre, inventory) / radius).sqrt();
+
+        // Keep the current direction of rotation
+        let direction = if position.perp_dot(ship.speed()) >= 0. {
+            1.
+        } else {
+            -1.
+        };
+        let orbital_velocity = position.perp().normalize_or_zero() * orbital_speed * direction;
+
+        let movement = (orbital_velocity - ship.speed()) / (ship.thrust() * delta);
+        ship.accelerate(movement.clamp_length_max(ORBIT_ASSIST_RATIO), delta);
+    }
+}
+
+// Integrates the Ship's trajectory through the current gravity field and draws it
+pub fn draw_trajectory_prediction(
+    mut gizmos: Gizmos,
+    flight_model: Res<FlightModel>,
+    q_ship: Single<(&Ship, &Transform, &ChildOf, &DockableOnAstre)>,
+    q_global_transforms: Query<&GlobalTransform>,
+    q_astres: Query<(&Astre, &Inventory, &GlobalTransform, &InheritedVisibility), Without<Ship>>,
+) {
+    if *flight_model != FlightModel::Newtonian {
+        return;
+    }
+
+    let (ship, transform, child_of, dockable) = q_ship.into_inner();
+
+    let Ok(frame_global_transform) = q_global_transforms.get(child_of.parent()) else {
+        return;
+    };
+
+    let bodies = gravity_bodies(
+        frame_global_transform,
+        q_astres
+            .iter()
+            .filter(|(_, _, _, v)| v.get())
+            .map(|(a, i, g, _)| (a, i, g)),
+    );
+
+    let start = transform.translation.truncate();
+    let mut position = start;
+    let mut speed = ship.speed();
+    let mut points = vec![position];
+    let mut color = TRAJECTORY_COLOR;
+
+    for _ in 0..TRAJECTORY_PREDICTION_STEPS {
+        speed += gravity_at(&bodies, position, dockable.on_astre) * TRAJECTORY_PREDICTION_STEP;
+        position += speed * TRAJECTORY_PREDICTION_STEP;
+        points.push(position);
+
+        if bodies
+            .iter()
+            .any(|b| b.position.distance(position) < b.radius)
+        {
+            color = TRAJECTORY_IMPACT_COLOR;
+            break;
+        }
+
+        // Closed the loop: stable orbit
+        if points.len() > 10
+            && position.distance(start) < speed.length() * TRAJECTORY_PREDICTION_STEP
+        {
+            color = TRAJECTORY_ORBIT_COLOR;
+            break;
+        }
+    }
+
+    gizmos.linestrip_2d(
+        points.into_iter().map(|point| {
+            frame_global_transform
+                .transform_point(point.extend(0.))
+                .truncate()
+        }),
+        color,
+    );
+}
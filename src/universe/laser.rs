@@ -6,23 +6,6 @@ use bevy::{
 };
 use rand::Rng;
 
-use crate::MaterialLoader;
-
-#[derive(Component, Reflect, Default)]
-#[reflect(Component, Default)]
-#[require(MaterialLoader<LaserMaterial>)]
-pub struct Laser {
-    ttl: Timer,
-}
-
-impl Laser {
-    pub fn new(seconds: f32) -> Self {
-        Self {
-            ttl: Timer::from_seconds(seconds, TimerMode::Once),
-        }
-    }
-}
-
 #[derive(Asset, AsBindGroup, Debug, Clone, Reflect, Default)]
 pub struct LaserMaterial {
     #[uniform(0)]
@@ -50,14 +33,9 @@ impl LaserMaterial {
     }
 }
 
-pub fn update_lasers(
-    mut commands: Commands,
-    time: Res<Time>,
-    mut q_lasers: Query<(Entity, &mut Laser)>,
-) {
-    for (entity, mut laser) in &mut q_lasers {
-        if laser.ttl.tick(time.delta()).is_finished() {
-            commands.entity(entity).despawn();
-        }
-    }
+// Transform of a beam child of the shooter, towards target (relative to the shooter)
+pub fn laser_beam_transform(target: Vec2, width: f32, z: f32) -> Transform {
+    Transform::from_translation((target / 2.0).extend(z))
+        .with_rotation(Quat::from_rotation_z((-target).y.atan2(-target.x)))
+        .with_scale(Vec3::new(target.length(), width, 1.))
 }
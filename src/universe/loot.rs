@@ -0,0 +1,60 @@
+use bevy::prelude::*;
+
+use crate::{SpriteLoader, items::Inventory, ui::NotificationEvent, universe::Ship};
+
+const LOOT_PICKUP_RANGE: f32 = 1000.;
+const LOOT_COLOR: Color = Color::srgb(1.0, 0.8, 0.2);
+
+// Items floating in space, collected by flying close to them
+#[derive(Component, Reflect, Default)]
+#[reflect(Component, Default)]
+#[require(Inventory)]
+pub struct Loot;
+
+pub fn build_loot(transform: Transform, inventory: Inventory) -> impl Bundle {
+    (
+        Name::new("Loot"),
+        Loot,
+        inventory,
+        SpriteLoader {
+            texture_path: "sprites/worm_segment.png".to_string(),
+            color: LOOT_COLOR,
+        },
+        transform,
+    )
+}
+
+pub fn collect_loot(
+    mut commands: Commands,
+    ship: Single<(&GlobalTransform, &mut Inventory), With<Ship>>,
+    mut q_loots: Query<(Entity, &GlobalTransform, &mut Inventory), (With<Loot>, Without<Ship>)>,
+) {
+    let (ship_global_transform, mut ship_inventory) = ship.into_inner();
+    let position = ship_global_transform.translation().truncate();
+
+    for (entity, global_transform, mut inventory) in &mut q_loots {
+        if global_transform.translation().truncate().distance(position) > LOOT_PICKUP_RANGE {
+            continue;
+        }
+
+        let mut collected = vec![];
+
+        for id in inventory.all_ids() {
+            let quantity = inventory.transfer_to(&mut ship_inventory, id, u32::MAX);
+            if quantity > 0 {
+                collected.push(format!("{} (x{quantity})", id.data().name));
+            }
+        }
+
+        if inventory.all_ids().is_empty() {
+            commands.entity(entity).despawn();
+        }
+
+        if !collected.is_empty() {
+            commands.trigger(NotificationEvent(format!(
+                "Collected {}",
+                collected.join(", ")
+            )));
+        }
+    }
+}
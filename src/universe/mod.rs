@@ -6,13 +6,20 @@ use crate::{GameSet, GameState, SolarSystemSet, UniverseMapSet, register_materia
 
 mod asteroid;
 mod astre;
+mod autopilot;
 mod background;
 mod camera;
 mod dockable_on_astre;
+mod dormant_solar_system;
+mod gravity;
 mod laser;
+mod loot;
 mod orbit;
 mod planet;
 mod ship;
+mod ship_damage;
+mod ship_modules;
+mod ship_weapon;
 mod solar_system;
 mod star;
 mod universe_map;
@@ -20,13 +27,20 @@ mod worm;
 
 pub use asteroid::*;
 pub use astre::*;
+pub use autopilot::*;
 pub use background::*;
 pub use camera::*;
 pub use dockable_on_astre::*;
+pub use dormant_solar_system::*;
+pub use gravity::*;
 pub use laser::*;
+pub use loot::*;
 pub use orbit::*;
 pub use planet::*;
 pub use ship::*;
+pub use ship_damage::*;
+pub use ship_modules::*;
+pub use ship_weapon::*;
 pub use solar_system::*;
 pub use star::*;
 pub use universe_map::*;
@@ -37,6 +51,7 @@ pub struct UniversePlugin;
 impl Plugin for UniversePlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(Material2dPlugin::<BackgroundMaterial>::default())
+            .init_resource::<FlightModel>()
             .add_systems(OnEnter(GameState::GameUniverseMap), spawn_universe_map)
             .add_systems(OnExit(GameState::GameUniverseMap), clean_universe_map)
             .add_systems(PreUpdate, (set_active_solar_system).in_set(SolarSystemSet))
@@ -49,15 +64,30 @@ impl Plugin for UniversePlugin {
                         spawn_ship_sprite,
                         scan_astres,
                         update_orbits,
-                        update_ship,
+                        (
+                            update_autopilot.before(update_ship),
+                            apply_gravity.before(update_ship),
+                            update_ship,
+                            update_ship_mining,
+                            update_ship_hazards.after(update_ship),
+                            update_last_spaceport,
+                            respawn_destroyed_ship.after(update_ship_hazards),
+                            draw_trajectory_prediction.after(update_ship),
+                            toggle_flight_model.run_if(input_just_pressed(KeyCode::KeyG)),
+                        ),
+                        apply_ship_modules,
                         update_planet_shadows,
                         update_worms,
-                        update_lasers,
+                        update_worm_nests,
+                        update_ship_weapon,
+                        kill_worms.after(update_ship_weapon),
+                        collect_loot,
                         update_asteroids,
                         reset_camera_viewport.run_if(input_just_pressed(KeyCode::KeyR)),
                     )
                         .in_set(SolarSystemSet),
                     (update_universe_map,).in_set(UniverseMapSet),
+                    (unload_idle_solar_systems, rehydrate_ship_solar_system).in_set(GameSet),
                     ((|state: Res<State<GameState>>,
                        mut next_state: ResMut<NextState<GameState>>| {
                         match state.get() {
@@ -79,7 +109,9 @@ impl Plugin for UniversePlugin {
                 (update_dockable_on_astre.after(TransformSystems::Propagate))
                     .in_set(SolarSystemSet),
             )
-            .add_observer(travel_to_solar_system);
+            .add_observer(travel_to_solar_system)
+            .add_observer(set_autopilot_target)
+            .add_observer(aim_ship_weapon);
 
         register_material!(app, PlanetMaterial);
         register_material!(app, StarMaterial);
@@ -1,58 +1,222 @@
+use std::time::Duration;
+
 use bevy::prelude::*;
 use rand::seq::IndexedRandom;
 
 use crate::{
     MaterialLoader, MeshType, SpriteLoader,
     buildings::PlacingBuilding,
-    data::ELEMENTS,
+    data::{ELEMENTS, ItemId, MiningLaserTier},
     items::{ElementState, Inventory},
     ui::NotificationEvent,
-    universe::{Astre, DockableOnAstre, Laser, LaserMaterial},
+    universe::{
+        Astre, Autopilot, DockableOnAstre, FlightModel, LaserMaterial, ShipModuleEffect,
+        ShipModules, build_weapon_laser, laser_beam_transform,
+    },
 };
 
 pub const SHIP_Z: f32 = 100.;
 
-const SHIP_INVENTORY_SIZE: u32 = 100_000;
+pub const SHIP_INVENTORY_SIZE: u32 = 100_000;
 
 pub const SHIP_ACTION_RANGE: f32 = 5000.;
 
-const MINING_COOLDOWN: f32 = 0.5;
-const MINING_AMOUNT_PER_TICK: u32 = 10;
+// New and respawned ships start here, outside of the biggest stars
+pub const SHIP_SPAWN_POSITION: Vec2 = Vec2::new(0., 40_000.);
+
+pub const SHIP_MAX_HULL: f32 = 100.;
+const SHIP_SHIELD_REGEN: f32 = 5.; // per second
+const SHIP_SHIELD_REGEN_DELAY: f32 = 5.; // seconds without damage before the shield regenerates
+
+const SHIP_MAX_SPEED: f32 = 50.;
+const SHIP_THRUST: f32 = 3000.;
+
 const MINING_LASER_WIDTH: f32 = 100.;
+const MINING_LASER_Z: f32 = -0.1;
 
-#[derive(Component, Reflect, Default)]
+pub struct MiningLaserData {
+    pub name: &'static str,
+    pub amount_per_tick: u32,
+    pub range: f32,
+    pub cooldown: f32,
+}
+
+// Stats are computed from the installed ShipModules
+#[derive(Component, Reflect)]
 #[reflect(Component, Default)]
+#[require(ShipModules)]
 pub struct Ship {
     speed: Vec2,
     max_speed: f32,
     thrust: f32,
     mining_cooldown: Timer,
     mining_amount_per_tick: u32,
+    mining_range: f32,
+    mining_laser: MiningLaserTier,
+    action_range: f32,
+    max_shield: f32,
+    hull: f32,
+    shield: f32,
+    shield_regen_cooldown: Timer,
+}
+
+// Also used for the fields missing from older saves
+impl Default for Ship {
+    fn default() -> Self {
+        let mut ship = Self {
+            speed: Vec2::ZERO,
+            max_speed: SHIP_MAX_SPEED,
+            thrust: SHIP_THRUST,
+            mining_cooldown: Timer::default(),
+            mining_amount_per_tick: 0,
+            mining_range: 0.,
+            mining_laser: MiningLaserTier::default(),
+            action_range: SHIP_ACTION_RANGE,
+            max_shield: 0.,
+            hull: SHIP_MAX_HULL,
+            shield: 0.,
+            shield_regen_cooldown: Timer::from_seconds(SHIP_SHIELD_REGEN_DELAY, TimerMode::Once),
+        };
+        ship.set_mining_laser(MiningLaserTier::default());
+        ship
+    }
 }
 
 impl Ship {
     pub fn speed(&self) -> Vec2 {
         self.speed
     }
+
+    pub fn max_speed(&self) -> f32 {
+        self.max_speed
+    }
+
+    pub fn thrust(&self) -> f32 {
+        self.thrust
+    }
+
+    pub fn mining_laser(&self) -> MiningLaserTier {
+        self.mining_laser
+    }
+
+    pub fn action_range(&self) -> f32 {
+        self.action_range
+    }
+
+    pub fn max_shield(&self) -> f32 {
+        self.max_shield
+    }
+
+    pub fn apply_modules(&mut self, modules: &ShipModules) {
+        self.max_speed = SHIP_MAX_SPEED;
+        self.thrust = SHIP_THRUST;
+        self.action_range = SHIP_ACTION_RANGE;
+        self.max_shield = 0.;
+
+        let mut mining_laser = MiningLaserTier::default();
+
+        for module in modules.modules() {
+            match module.data().effect {
+                ShipModuleEffect::Cargo(_) => {}
+                ShipModuleEffect::Thruster { thrust, max_speed } => {
+                    self.thrust += thrust;
+                    self.max_speed += max_speed;
+                }
+                ShipModuleEffect::MiningLaser(tier) => {
+                    if tier.data().amount_per_tick > mining_laser.data().amount_per_tick {
+                        mining_laser = tier;
+                    }
+                }
+                ShipModuleEffect::Scanner(range) => self.action_range += range,
+                ShipModuleEffect::Shield(shield) => self.max_shield += shield,
+            }
+        }
+
+        self.set_mining_laser(mining_laser);
+        self.shield = self.shield.min(self.max_shield);
+    }
+
+    pub fn hull(&self) -> f32 {
+        self.hull
+    }
+
+    pub fn shield(&self) -> f32 {
+        self.shield
+    }
+
+    pub fn is_destroyed(&self) -> bool {
+        self.hull <= 0.
+    }
+
+    // The shield absorbs damage before the hull
+    pub fn damage(&mut self, amount: f32) {
+        let absorbed = amount.min(self.shield);
+        self.shield -= absorbed;
+        self.hull = (self.hull - (amount - absorbed)).max(0.);
+        self.shield_regen_cooldown.reset();
+    }
+
+    pub fn regenerate_shield(&mut self, delta: Duration) {
+        if self.shield_regen_cooldown.tick(delta).is_finished() {
+            self.shield =
+                (self.shield + SHIP_SHIELD_REGEN * delta.as_secs_f32()).min(self.max_shield);
+        }
+    }
+
+    pub fn repair(&mut self) {
+        self.hull = SHIP_MAX_HULL;
+        self.shield = self.max_shield;
+        self.speed = Vec2::ZERO;
+    }
+
+    // Bounce off a surface of the given normal, keeping restitution of the speed
+    pub fn bounce(&mut self, normal: Vec2, restitution: f32) {
+        self.speed = self.speed.reflect(normal) * restitution;
+    }
+
+    // The length of movement is the ratio of thrust used
+    pub fn accelerate(&mut self, movement: Vec2, delta: f32) {
+        self.speed += (movement * self.thrust * delta).clamp_length_max(self.max_speed);
+    }
+
+    // External acceleration, not limited by the Ship's thrust (gravity)
+    pub fn apply_acceleration(&mut self, acceleration: Vec2, delta: f32) {
+        self.speed += acceleration * delta;
+    }
+
+    pub fn brake(&mut self) {
+        self.speed *= 0.9;
+    }
+
+    fn set_mining_laser(&mut self, tier: MiningLaserTier) {
+        let data = tier.data();
+        self.mining_laser = tier;
+        self.mining_amount_per_tick = data.amount_per_tick;
+        self.mining_range = data.range;
+        self.mining_cooldown = Timer::from_seconds(data.cooldown, TimerMode::Once);
+    }
 }
 
 #[derive(Component)]
 pub struct ShipSprite;
 
-pub fn build_ship() -> impl Bundle {
-    let position = Vec2::new(0., 0.);
+// Beam shown while mining, stretched from the Ship to the mined point
+#[derive(Component)]
+pub struct MiningLaser;
+
+// Astre being mined and mined point, relative to the astre
+#[derive(Component)]
+pub struct MiningTarget {
+    astre: Entity,
+    position: Vec2,
+}
 
+pub fn build_ship() -> impl Bundle {
     (
         Name::new("Ship"),
-        Ship {
-            speed: Vec2::new(0., 0.),
-            max_speed: 50.,
-            thrust: 3000.,
-            mining_cooldown: Timer::from_seconds(MINING_COOLDOWN, TimerMode::Once),
-            mining_amount_per_tick: MINING_AMOUNT_PER_TICK,
-        },
+        Ship::default(),
         DockableOnAstre::default(),
-        Transform::from_translation(position.extend(SHIP_Z)),
+        Transform::from_translation(SHIP_SPAWN_POSITION.extend(SHIP_Z)),
         Visibility::default(),
         Inventory::new(SHIP_INVENTORY_SIZE),
     )
@@ -68,23 +232,24 @@ pub fn spawn_ship_sprite(mut commands: Commands, ship: Single<Entity, Added<Ship
                 ..default()
             },
         ));
+
+        c.spawn((
+            MiningLaser,
+            MaterialLoader {
+                mesh_type: MeshType::Rectangle(Vec2::splat(-0.5), Vec2::splat(0.5)),
+                material: LaserMaterial::new(Color::WHITE.into()),
+            },
+            Transform::from_xyz(0., 0., MINING_LASER_Z),
+            Visibility::Hidden,
+        ));
+
+        c.spawn(build_weapon_laser());
     });
 }
 
-pub fn update_ship(
-    time: Res<Time>,
-    keyboard_input: Res<ButtonInput<KeyCode>>,
-    q_ship: Single<(&mut Ship, &mut Transform, &DockableOnAstre), Without<ShipSprite>>,
-    mut ship_sprite_transform: Single<&mut Transform, With<ShipSprite>>,
-) {
-    let (mut ship, mut transform, dockable) = q_ship.into_inner();
-
+pub fn ship_movement_input(keyboard_input: &ButtonInput<KeyCode>) -> Vec2 {
     let mut movement = Vec2::new(0., 0.);
 
-    if keyboard_input.pressed(KeyCode::Space) {
-        ship.speed *= 0.9;
-    }
-
     if keyboard_input.any_pressed(vec![KeyCode::ArrowLeft, KeyCode::KeyA]) {
         movement.x -= 1.;
     }
@@ -98,13 +263,43 @@ pub fn update_ship(
         movement.y -= 1.;
     }
 
-    let acceleration = movement * ship.thrust;
+    movement
+}
+
+pub fn update_ship(
+    mut commands: Commands,
+    time: Res<Time>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    flight_model: Res<FlightModel>,
+    q_ship: Single<
+        (
+            Entity,
+            &mut Ship,
+            &mut Transform,
+            &DockableOnAstre,
+            Has<Autopilot>,
+        ),
+        Without<ShipSprite>,
+    >,
+    mut ship_sprite_transform: Single<&mut Transform, With<ShipSprite>>,
+) {
+    let (ship_entity, mut ship, mut transform, dockable, autopilot) = q_ship.into_inner();
+
+    if keyboard_input.pressed(KeyCode::Space) {
+        ship.brake();
+    }
+
+    let movement = ship_movement_input(&keyboard_input);
 
-    let max_speed = ship.max_speed;
+    // Any manual input takes back control from the autopilot
+    if autopilot && (movement != Vec2::ZERO || keyboard_input.pressed(KeyCode::Space)) {
+        commands.entity(ship_entity).remove::<Autopilot>();
+        commands.trigger(NotificationEvent("Autopilot disengaged".to_string()));
+    }
 
-    ship.speed += (acceleration * time.delta_secs()).clamp_length_max(max_speed);
+    ship.accelerate(movement, time.delta_secs());
 
-    if dockable.on_astre {
+    if dockable.on_astre && *flight_model == FlightModel::Arcade {
         ship.speed *= 0.99;
     }
 
@@ -123,86 +318,169 @@ pub fn update_ship(
     }
 }
 
-pub fn update_ship_mining(
-    pointer_click: On<Pointer<Click>>,
+pub fn start_ship_mining(
+    pointer_press: On<Pointer<Press>>,
     mut commands: Commands,
     placing_building: Option<Res<PlacingBuilding>>,
-    q_ship: Single<(Entity, &Ship, &GlobalTransform, &mut Inventory)>,
+    ship: Single<Entity, With<Ship>>,
+    q_global_transforms: Query<&GlobalTransform, With<Astre>>,
+) {
+    if placing_building.is_some() || pointer_press.button != PointerButton::Primary {
+        return;
+    }
+
+    if let Ok(astre_global_transform) = q_global_transforms.get(pointer_press.entity)
+        && let Some(position) = pointer_press.hit.position
+    {
+        commands.entity(*ship).insert(MiningTarget {
+            astre: pointer_press.entity,
+            position: astre_global_transform
+                .affine()
+                .inverse()
+                .transform_point3(position)
+                .truncate(),
+        });
+    }
+}
+
+pub fn aim_ship_mining(
+    pointer_move: On<Pointer<Move>>,
+    mut q_mining_target: Query<&mut MiningTarget>,
+    q_global_transforms: Query<&GlobalTransform, With<Astre>>,
+) {
+    if let Ok(mut mining_target) = q_mining_target.single_mut()
+        && mining_target.astre == pointer_move.entity
+        && let Ok(astre_global_transform) = q_global_transforms.get(pointer_move.entity)
+        && let Some(position) = pointer_move.hit.position
+    {
+        mining_target.position = astre_global_transform
+            .affine()
+            .inverse()
+            .transform_point3(position)
+            .truncate();
+    }
+}
+
+#[derive(Event)]
+pub struct ShipMined {
+    pub ship: Entity,
+    pub astre: Entity,
+    pub item: ItemId,
+    pub quantity: u32,
+}
+
+// Mines the MiningTarget while the mouse button is held, once per mining cooldown
+pub fn update_ship_mining(
+    mut commands: Commands,
+    time: Res<Time>,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    mut laser_materials: ResMut<Assets<LaserMaterial>>,
+    q_ship: Single<(
+        Entity,
+        &mut Ship,
+        &GlobalTransform,
+        &mut Inventory,
+        Option<&MiningTarget>,
+    )>,
     mut q_astres: Query<(&Astre, &mut Inventory, &GlobalTransform), Without<Ship>>,
+    mining_laser: Single<
+        (
+            &mut Transform,
+            &mut Visibility,
+            Option<&MeshMaterial2d<LaserMaterial>>,
+        ),
+        With<MiningLaser>,
+    >,
 ) {
-    let (ship_entity, ship, transform, mut inventory) = q_ship.into_inner();
+    let (ship_entity, mut ship, transform, mut inventory, mining_target) = q_ship.into_inner();
+    let (mut laser_transform, mut laser_visibility, laser_material) = mining_laser.into_inner();
 
-    if placing_building.is_some() {
+    ship.mining_cooldown.tick(time.delta());
+
+    *laser_visibility = Visibility::Hidden;
+
+    let Some(mining_target) = mining_target else {
+        return;
+    };
+
+    if !mouse_input.pressed(MouseButton::Left) {
+        commands.entity(ship_entity).remove::<MiningTarget>();
         return;
     }
 
-    // TODO ship.mining_cooldown.tick(time.delta()).finished() ; ship.mining_cooldown.reset();
+    let Ok((astre, mut astre_inventory, astre_global_transform)) =
+        q_astres.get_mut(mining_target.astre)
+    else {
+        commands.entity(ship_entity).remove::<MiningTarget>();
+        return;
+    };
+
+    let position = astre_global_transform
+        .transform_point(mining_target.position.extend(0.))
+        .truncate();
+    let ship_position = transform.translation().truncate();
+    let astre_position = astre_global_transform.translation().truncate();
 
-    if let Ok((astre, mut astre_inventory, astre_global_transform)) =
-        q_astres.get_mut(pointer_click.entity)
-        && let Some(position) = pointer_click.hit.position
+    if position.distance(astre_position) >= astre.atmosphere_radius()
+        || position.distance(ship_position) >= ship.mining_range
     {
-        let position = position.truncate();
-        let ship_position = transform.translation().truncate();
-        let astre_position = astre_global_transform.translation().truncate();
+        return;
+    }
+
+    // Laser beam
+    *laser_visibility = Visibility::Inherited;
+    *laser_transform =
+        laser_beam_transform(position - ship_position, MINING_LASER_WIDTH, MINING_LASER_Z);
+
+    if !ship.mining_cooldown.is_finished() {
+        return;
+    }
+
+    ship.mining_cooldown.reset();
+
+    let atmosphere_mining = position.distance(astre_position) > astre.surface_radius();
+
+    let item_ids = astre_inventory
+        .all_ids()
+        .iter()
+        .filter(|id| {
+            ELEMENTS
+                .get(*id)
+                .is_some_and(|e| !atmosphere_mining || e.state == ElementState::Gas)
+        })
+        .copied()
+        .collect::<Vec<_>>();
 
-        if position.distance(astre_position) < astre.atmosphere_radius()
-            && position.distance(ship_position) < SHIP_ACTION_RANGE
+    let mut rng = rand::rng();
+    let random_item_id = item_ids.choose_weighted(&mut rng, |id| astre_inventory.quantity(*id));
+
+    if let Ok(item_id) = random_item_id {
+        let quantity = astre_inventory
+            .quantity(*item_id)
+            .min(ship.mining_amount_per_tick);
+
+        astre_inventory.transfer_to(&mut inventory, *item_id, quantity);
+
+        if let Some(material) =
+            laser_material.and_then(|material| laser_materials.get_mut(&material.0))
         {
-            let atmosphere_mining = position.distance(astre_position) > astre.surface_radius();
-
-            let item_ids = astre_inventory
-                .all_ids()
-                .iter()
-                .filter(|id| {
-                    ELEMENTS
-                        .get(*id)
-                        .is_some_and(|e| !atmosphere_mining || e.state == ElementState::Gas)
-                })
-                .copied()
-                .collect::<Vec<_>>();
-
-            let mut rng = rand::rng();
-            let random_item_id =
-                item_ids.choose_weighted(&mut rng, |id| astre_inventory.quantity(*id));
-
-            if let Ok(item_id) = random_item_id {
-                let quantity = astre_inventory
-                    .quantity(*item_id)
-                    .min(ship.mining_amount_per_tick);
-
-                astre_inventory.transfer_to(&mut inventory, *item_id, quantity);
-
-                // Laser beam
-                let color = ELEMENTS
-                    .get(item_id)
-                    .map_or(Color::WHITE.into(), |e| e.color.into());
-
-                let relative_position = ship_position - position;
-                let angle = relative_position.y.atan2(relative_position.x);
-
-                commands.entity(ship_entity).with_children(|c| {
-                    c.spawn((
-                        Laser::new(0.5),
-                        MaterialLoader {
-                            mesh_type: MeshType::Rectangle(
-                                Vec2::ZERO,
-                                Vec2::new(relative_position.length(), MINING_LASER_WIDTH),
-                            ),
-                            material: LaserMaterial::new(color),
-                        },
-                        Transform::from_translation((-relative_position / 2.0).extend(-0.1))
-                            .with_rotation(Quat::from_rotation_z(angle)),
-                    ));
-                });
-
-                let item = item_id.data();
-
-                commands.trigger(NotificationEvent(format!(
-                    "Mined {} (x{quantity})",
-                    item.name
-                )));
-            }
+            material.color = ELEMENTS
+                .get(item_id)
+                .map_or(Color::WHITE.into(), |e| e.color.into());
         }
+
+        commands.trigger(ShipMined {
+            ship: ship_entity,
+            astre: mining_target.astre,
+            item: *item_id,
+            quantity,
+        });
+
+        let item = item_id.data();
+
+        commands.trigger(NotificationEvent(format!(
+            "Mined {} (x{quantity})",
+            item.name
+        )));
     }
 }
@@ -0,0 +1,182 @@
+use bevy::prelude::*;
+
+use crate::{
+    buildings::Spaceport,
+    items::Inventory,
+    ui::NotificationEvent,
+    universe::{
+        ActiveSolarSystem, Astre, Autopilot, DockableOnAstre, MiningTarget, SHIP_MAX_HULL,
+        SHIP_SPAWN_POSITION, SHIP_Z, Ship, Star, Worm, WormSegment, touches_worm_part,
+    },
+};
+
+// Stars burn ships closer than this ratio of their radius above their surface
+const STAR_HEAT_RANGE_RATIO: f32 = 0.25;
+const STAR_HEAT_DAMAGE: f32 = 30.; // per second, at the surface
+
+// Slower impacts on a surface are landings
+const SAFE_LANDING_SPEED: f32 = 300.;
+const COLLISION_DAMAGE_RATIO: f32 = 0.05; // damage per unit of speed above SAFE_LANDING_SPEED
+const COLLISION_RESTITUTION: f32 = 0.3;
+
+const WORM_CONTACT_DAMAGE: f32 = 20.; // per second
+
+const CRITICAL_HULL_RATIO: f32 = 0.25;
+
+// Last Spaceport the Ship came within action range of, where it respawns when destroyed
+#[derive(Component)]
+pub struct LastSpaceport(Entity);
+
+pub fn update_ship_hazards(
+    mut commands: Commands,
+    time: Res<Time>,
+    q_ship: Single<(&mut Ship, &Transform, &GlobalTransform, &ChildOf)>,
+    q_astres: Query<(&Astre, Has<Star>, &GlobalTransform, &InheritedVisibility), Without<Ship>>,
+    q_worm_parts: Query<
+        (&GlobalTransform, Has<Worm>, &InheritedVisibility),
+        Or<(With<Worm>, With<WormSegment>)>,
+    >,
+) {
+    let (mut ship, transform, global_transform, child_of) = q_ship.into_inner();
+
+    let hull_before = ship.hull();
+    let position = global_transform.translation().truncate();
+    let delta = time.delta_secs();
+
+    ship.regenerate_shield(time.delta());
+
+    let mut damage = 0.;
+
+    // Star heat, growing towards the surface
+    for (astre, _, astre_global_transform, _) in q_astres
+        .iter()
+        .filter(|(_, is_star, _, v)| *is_star && v.get())
+    {
+        let heat_range = astre.surface_radius() * STAR_HEAT_RANGE_RATIO;
+        let altitude = astre_global_transform
+            .translation()
+            .truncate()
+            .distance(position)
+            - astre.surface_radius();
+
+        if altitude < heat_range {
+            let intensity = (1. - altitude / heat_range).min(1.);
+            damage += STAR_HEAT_DAMAGE * intensity * delta;
+        }
+    }
+
+    // Worms
+    if q_worm_parts.iter().any(|(worm_global_transform, head, v)| {
+        v.get() && touches_worm_part(worm_global_transform, head, position)
+    }) {
+        damage += WORM_CONTACT_DAMAGE * delta;
+    }
+
+    if damage > 0. {
+        ship.damage(damage);
+    }
+
+    // Crashing on the surface of the astre the Ship is docked on (position is relative to it)
+    if let Ok((astre, false, _, _)) = q_astres.get(child_of.parent()) {
+        let local_position = transform.translation.truncate();
+        let normal = local_position.normalize_or_zero();
+        let impact_speed = -ship.speed().dot(normal);
+
+        if local_position.length() < astre.surface_radius() && impact_speed > SAFE_LANDING_SPEED {
+            ship.damage((impact_speed - SAFE_LANDING_SPEED) * COLLISION_DAMAGE_RATIO);
+            ship.bounce(normal, COLLISION_RESTITUTION);
+        }
+    }
+
+    let critical_hull = SHIP_MAX_HULL * CRITICAL_HULL_RATIO;
+
+    if hull_before > critical_hull && ship.hull() <= critical_hull && !ship.is_destroyed() {
+        commands.trigger(NotificationEvent("Hull critical!".to_string()));
+    }
+}
+
+pub fn update_last_spaceport(
+    mut commands: Commands,
+    q_ship: Single<(Entity, &Ship, &GlobalTransform, Option<&LastSpaceport>)>,
+    q_spaceports: Query<(Entity, &GlobalTransform), With<Spaceport>>,
+) {
+    let (ship_entity, ship, global_transform, last_spaceport) = q_ship.into_inner();
+
+    let position = global_transform.translation().truncate();
+
+    if let Some((spaceport, _)) = q_spaceports.iter().find(|(_, spaceport_global_transform)| {
+        spaceport_global_transform
+            .translation()
+            .truncate()
+            .distance(position)
+            < ship.action_range()
+    }) && last_spaceport.is_none_or(|last| last.0 != spaceport)
+    {
+        commands
+            .entity(ship_entity)
+            .insert(LastSpaceport(spaceport));
+    }
+}
+
+// Respawns a destroyed Ship at its LastSpaceport if it is in the active solar system,
+// or at the edge of the active solar system. Half of the cargo is lost.
+pub fn respawn_destroyed_ship(
+    mut commands: Commands,
+    q_ship: Single<(
+        Entity,
+        &mut Ship,
+        &mut Inventory,
+        &mut DockableOnAstre,
+        Option<&LastSpaceport>,
+    )>,
+    active_solar_system: Single<(Entity, &GlobalTransform), With<ActiveSolarSystem>>,
+    q_global_transforms: Query<&GlobalTransform>,
+    q_parents: Query<&ChildOf>,
+) {
+    let (ship_entity, mut ship, mut inventory, mut dockable, last_spaceport) = q_ship.into_inner();
+
+    if !ship.is_destroyed() {
+        return;
+    }
+
+    let (solar_system, solar_system_global_transform) = *active_solar_system;
+
+    let spaceport_transform = last_spaceport
+        .filter(|last| {
+            q_parents
+                .iter_ancestors(last.0)
+                .any(|ancestor| ancestor == solar_system)
+        })
+        .and_then(|last| q_global_transforms.get(last.0).ok())
+        .map(|spaceport_global_transform| {
+            spaceport_global_transform.reparented_to(solar_system_global_transform)
+        });
+
+    let mut transform =
+        spaceport_transform.unwrap_or(Transform::from_translation(SHIP_SPAWN_POSITION.extend(0.)));
+    transform.translation.z = SHIP_Z;
+    transform.rotation = Quat::IDENTITY;
+    transform.scale = Vec3::ONE;
+
+    commands
+        .entity(ship_entity)
+        .insert((ChildOf(solar_system), transform))
+        .remove::<(Autopilot, MiningTarget)>();
+
+    ship.repair();
+    dockable.on_astre = false;
+
+    for id in inventory.all_ids() {
+        let quantity = inventory.quantity(id) / 2;
+        inventory.try_remove(id, quantity);
+    }
+
+    commands.trigger(NotificationEvent(format!(
+        "Ship destroyed! Respawned {}, half of the cargo was lost",
+        if spaceport_transform.is_some() {
+            "at the last spaceport"
+        } else {
+            "at the edge of the solar system"
+        }
+    )));
+}
@@ -0,0 +1,73 @@
+use bevy::prelude::*;
+
+use crate::{
+    data::{ItemId, MiningLaserTier, ShipModuleId},
+    items::Inventory,
+    universe::{SHIP_INVENTORY_SIZE, Ship},
+};
+
+pub const SHIP_MODULE_SLOTS: usize = 4;
+
+pub struct ShipModuleData {
+    pub item: ItemId, // consumed when installed, given back when uninstalled
+    pub effect: ShipModuleEffect,
+}
+
+pub enum ShipModuleEffect {
+    Cargo(u32),
+    Thruster { thrust: f32, max_speed: f32 },
+    MiningLaser(MiningLaserTier), // the best installed mining laser is used
+    Scanner(f32),                 // action range
+    Shield(f32),
+}
+
+#[derive(Component, Reflect, Default, Debug)]
+#[reflect(Component, Default)]
+pub struct ShipModules {
+    modules: Vec<ShipModuleId>,
+}
+
+impl ShipModules {
+    pub fn modules(&self) -> &[ShipModuleId] {
+        &self.modules
+    }
+
+    pub fn has_free_slot(&self) -> bool {
+        self.modules.len() < SHIP_MODULE_SLOTS
+    }
+
+    pub fn install(&mut self, module: ShipModuleId) -> bool {
+        if self.has_free_slot() {
+            self.modules.push(module);
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn uninstall(&mut self, slot: usize) -> Option<ShipModuleId> {
+        (slot < self.modules.len()).then(|| self.modules.remove(slot))
+    }
+
+    pub fn cargo_size(&self) -> u32 {
+        SHIP_INVENTORY_SIZE
+            + self
+                .modules
+                .iter()
+                .map(|module| match module.data().effect {
+                    ShipModuleEffect::Cargo(size) => size,
+                    _ => 0,
+                })
+                .sum::<u32>()
+    }
+}
+
+// Recomputes the Ship stats from its installed modules (also when the Ship is spawned or loaded)
+pub fn apply_ship_modules(
+    mut q_ships: Query<(&mut Ship, &mut Inventory, &ShipModules), Changed<ShipModules>>,
+) {
+    for (mut ship, mut inventory, modules) in &mut q_ships {
+        ship.apply_modules(modules);
+        inventory.set_size(modules.cargo_size());
+    }
+}
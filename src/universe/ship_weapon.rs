@@ -0,0 +1,106 @@
+use bevy::prelude::*;
+
+use crate::{
+    MaterialLoader, MeshType,
+    buildings::PlacingBuilding,
+    universe::{LaserMaterial, Ship, Worm, WormSegment, laser_beam_transform},
+};
+
+const WEAPON_RANGE: f32 = 4000.;
+const WEAPON_DAMAGE: f32 = 25.; // per second
+const WEAPON_LASER_WIDTH: f32 = 40.;
+const WEAPON_LASER_Z: f32 = -0.2;
+const WEAPON_LASER_COLOR: Color = Color::srgb(1.0, 0.1, 0.1);
+
+// Beam shown while firing, stretched from the Ship to the targeted worm part
+#[derive(Component)]
+pub struct WeaponLaser;
+
+// Worm head or segment being fired at
+#[derive(Component)]
+pub struct WeaponTarget(Entity);
+
+pub fn build_weapon_laser() -> impl Bundle {
+    (
+        WeaponLaser,
+        MaterialLoader {
+            mesh_type: MeshType::Rectangle(Vec2::splat(-0.5), Vec2::splat(0.5)),
+            material: LaserMaterial::new(WEAPON_LASER_COLOR.into()),
+        },
+        Transform::from_xyz(0., 0., WEAPON_LASER_Z),
+        Visibility::Hidden,
+    )
+}
+
+// Press on a worm to fire at it
+pub fn aim_ship_weapon(
+    mut pointer_press: On<Pointer<Press>>,
+    mut commands: Commands,
+    placing_building: Option<Res<PlacingBuilding>>,
+    ship: Single<Entity, With<Ship>>,
+    q_worm_parts: Query<(), Or<(With<Worm>, With<WormSegment>)>>,
+) {
+    if placing_building.is_some()
+        || pointer_press.button != PointerButton::Primary
+        || !q_worm_parts.contains(pointer_press.entity)
+    {
+        return;
+    }
+
+    // Segments are children of the worm head
+    pointer_press.propagate(false);
+
+    commands
+        .entity(*ship)
+        .insert(WeaponTarget(pointer_press.entity));
+}
+
+// Fires at the WeaponTarget while the mouse button is held
+pub fn update_ship_weapon(
+    mut commands: Commands,
+    time: Res<Time>,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    q_ship: Single<(Entity, &GlobalTransform, Option<&WeaponTarget>), With<Ship>>,
+    weapon_laser: Single<(&mut Transform, &mut Visibility), With<WeaponLaser>>,
+    q_worm_parts: Query<(&GlobalTransform, Option<&ChildOf>), Or<(With<Worm>, With<WormSegment>)>>,
+    mut q_worms: Query<&mut Worm>,
+) {
+    let (ship_entity, ship_global_transform, weapon_target) = q_ship.into_inner();
+    let (mut laser_transform, mut laser_visibility) = weapon_laser.into_inner();
+
+    *laser_visibility = Visibility::Hidden;
+
+    let Some(weapon_target) = weapon_target else {
+        return;
+    };
+
+    let Ok((part_global_transform, part_child_of)) = q_worm_parts.get(weapon_target.0) else {
+        commands.entity(ship_entity).remove::<WeaponTarget>();
+        return;
+    };
+
+    if !mouse_input.pressed(MouseButton::Left) {
+        commands.entity(ship_entity).remove::<WeaponTarget>();
+        return;
+    }
+
+    let target = part_global_transform.translation().truncate()
+        - ship_global_transform.translation().truncate();
+
+    if target.length() >= WEAPON_RANGE {
+        return;
+    }
+
+    *laser_visibility = Visibility::Inherited;
+    *laser_transform = laser_beam_transform(target, WEAPON_LASER_WIDTH, WEAPON_LASER_Z);
+
+    let worm = if q_worms.contains(weapon_target.0) {
+        Some(weapon_target.0)
+    } else {
+        part_child_of.map(ChildOf::parent)
+    };
+
+    if let Some(mut worm) = worm.and_then(|worm| q_worms.get_mut(worm).ok()) {
+        worm.damage(WEAPON_DAMAGE * time.delta_secs());
+    }
+}
@@ -1,10 +1,11 @@
 use bevy::{ecs::spawn::SpawnIter, prelude::*};
 use rand::prelude::*;
 
-use crate::universe::{build_star, build_worm};
+use crate::universe::{SolarSystemIdle, build_star, build_worm, build_worm_nest};
 
 #[derive(Component, Reflect, Default)]
 #[reflect(Component, Default)]
+#[require(SolarSystemIdle)]
 pub struct SolarSystem {
     pub position: [i32; 2],
 }
@@ -30,6 +31,10 @@ pub fn build_solar_system(position: [i32; 2]) -> impl Bundle {
     let mut rng: StdRng = SeedableRng::seed_from_u64(solar_system.seed());
 
     let nb_worms = 3;
+    let nb_worm_nests = 2;
+
+    // Separate generator, so that nests don't shift the generation of the other entities
+    let mut nest_rng: StdRng = SeedableRng::seed_from_u64(solar_system.seed().wrapping_add(1));
 
     (
         Name::new("SolarSytem"),
@@ -46,6 +51,13 @@ pub fn build_solar_system(position: [i32; 2]) -> impl Bundle {
                 );
                 build_worm(&mut rng, worm_position)
             })),
+            SpawnIter((0..nb_worm_nests).map(move |_| {
+                let nest_spawn_radius = 80000.;
+                build_worm_nest(Vec2::new(
+                    nest_rng.random_range(-nest_spawn_radius..nest_spawn_radius),
+                    nest_rng.random_range(-nest_spawn_radius..nest_spawn_radius),
+                ))
+            })),
         )),
     )
 }
@@ -7,7 +7,9 @@ use rand::prelude::*;
 use super::ActiveSolarSystem;
 use crate::{
     GameState, SaveUniverse,
-    universe::{MainCamera, Ship, SolarSystem, build_solar_system, build_star},
+    universe::{
+        MainCamera, Ship, SolarSystem, assign_procedural_ids, build_solar_system, build_star,
+    },
 };
 
 const OBSERVABLE_UNIVERSE_RADIUS: i32 = 5;
@@ -180,7 +182,7 @@ pub fn travel_to_solar_system(
     info!("Travelling to solar system at {:?}", travel.0);
 
     // save game just in case
-    commands.queue(SaveUniverse);
+    commands.queue(SaveUniverse::default());
 
     let solar_system_position = travel.0;
 
@@ -200,6 +202,7 @@ pub fn travel_to_solar_system(
         } else {
             commands
                 .spawn(build_solar_system(solar_system_position))
+                .queue(assign_procedural_ids)
                 .id()
         }
     };
@@ -3,14 +3,45 @@ use std::f32::consts::PI;
 use bevy::{ecs::spawn::SpawnIter, prelude::*};
 use rand::prelude::*;
 
-use crate::{SpriteLoader, universe::SHIP_Z};
+use crate::{
+    SpriteLoader,
+    buildings::{BuildingHealth, BuildingHighlight},
+    data::ItemId,
+    items::Inventory,
+    ui::NotificationEvent,
+    universe::{Asteroid, Astre, SHIP_Z, Ship, build_loot},
+};
 
 const WORM_Z: f32 = SHIP_Z - 2.0;
 const WORM_Z_DELTA: f32 = 0.001;
 const SEGMENT_WIDTH: f32 = 80.;
 const HEAD_WIDTH: f32 = 160.;
 
-#[derive(Component, Reflect, Default)]
+const WORM_HEALTH_PER_SEGMENT: f32 = 10.;
+const WORM_AGGRO_RADIUS: f32 = 8000.;
+const WORM_TURN_ANGLE: f32 = PI / 8.; // max turn towards the target, each direction change
+const WORM_BITE: u32 = 50; // items eaten per bite
+const WORM_BITE_DAMAGE: f32 = 10.; // to buildings, per bite
+
+// Buildings attract worms from further away the more items they store
+const WORM_ATTRACTION_PER_ITEM: f32 = 0.5;
+const WORM_MAX_ATTRACTION_RADIUS: f32 = 50_000.;
+
+// Hungry worms look for asteroids to eat
+const WORM_HUNGER_THRESHOLD: f32 = 30.; // seconds since the last meal
+const WORM_SMELL_RADIUS: f32 = 60_000.;
+const WORM_STARVATION_TIME: f32 = 600.; // seconds since the last meal before losing a segment
+
+const WORM_GROWTH: u32 = 500; // items eaten to grow a segment
+const WORM_MAX_LENGTH: u32 = 80;
+
+const WORM_HATCHLING_LENGTH: u32 = 3;
+const WORM_NEST_SPAWN_INTERVAL: f32 = 180.;
+const WORM_MAX_POPULATION: usize = 8; // per solar system
+const WORM_NEST_SIZE: f32 = 20.;
+const WORM_NEST_COLOR: Color = Color::srgb(0.3, 0.15, 0.2);
+
+#[derive(Component, Reflect, Default, Clone, PartialEq, Debug)]
 #[reflect(Component, Default)]
 pub struct Worm {
     length: u32,
@@ -19,15 +50,82 @@ pub struct Worm {
     change_direction_cooldown: Timer,
     seed: f32,
     wiggle_amplitude: f32,
+    damage: f32,
+    hunger: f32, // seconds since the last meal
+    eaten: u32,  // items eaten since the last growth
 }
 
+impl Worm {
+    pub fn length(&self) -> u32 {
+        self.length
+    }
+
+    pub fn max_health(&self) -> f32 {
+        WORM_HEALTH_PER_SEGMENT * (self.length + 1) as f32
+    }
+
+    pub fn damage(&mut self, amount: f32) {
+        self.damage += amount;
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.damage >= self.max_health()
+    }
+
+    fn eat(&mut self, quantity: u32) {
+        self.eaten += quantity;
+        self.hunger = 0.;
+    }
+}
+
+// Spawns hatchlings while the solar system is under-populated
+#[derive(Component, Reflect)]
+#[reflect(Component, Default)]
+pub struct WormNest {
+    spawn_cooldown: Timer,
+}
+
+impl Default for WormNest {
+    fn default() -> Self {
+        Self {
+            spawn_cooldown: Timer::from_seconds(WORM_NEST_SPAWN_INTERVAL, TimerMode::Repeating),
+        }
+    }
+}
+
+// Ship or building the worm is hunting
+#[derive(Component)]
+pub struct WormAggro(Entity);
+
 #[derive(Component, Reflect, Default)]
 #[reflect(Component, Default)]
 pub struct WormSegment;
 
+// Whether position touches the head or a segment of a worm, given its GlobalTransform
+pub fn touches_worm_part(global_transform: &GlobalTransform, head: bool, position: Vec2) -> bool {
+    let width = if head { HEAD_WIDTH } else { SEGMENT_WIDTH };
+    let radius = width / 2. * global_transform.scale().x;
+
+    global_transform.translation().truncate().distance(position) < radius
+}
+
 pub fn build_worm(rng: &mut StdRng, position: Vec2) -> impl Bundle + use<> {
+    build_worm_with_length(rng, position, None)
+}
+
+// Short worm born in a nest
+pub fn build_worm_hatchling(rng: &mut StdRng, position: Vec2) -> impl Bundle + use<> {
+    build_worm_with_length(rng, position, Some(WORM_HATCHLING_LENGTH))
+}
+
+fn build_worm_with_length(
+    rng: &mut StdRng,
+    position: Vec2,
+    length: Option<u32>,
+) -> impl Bundle + use<> {
     let size = rng.random_range(1. ..=10.);
-    let length = rng.random_range(5..=50);
+    let random_length = rng.random_range(5..=50);
+    let length = length.unwrap_or(random_length);
     let speed = rng.random_range(100. ..=1000.);
     let wiggle_amplitude = rng.random_range(5. ..=15.);
     let change_direction_every = rng.random_range(0.1..=3.);
@@ -51,52 +149,235 @@ pub fn build_worm(rng: &mut StdRng, position: Vec2) -> impl Bundle + use<> {
             change_direction_cooldown: Timer::from_seconds(change_direction_every, TimerMode::Once),
             seed: rng.random(),
             wiggle_amplitude,
+            damage: 0.,
+            hunger: 0.,
+            eaten: 0,
         },
         SpriteLoader {
             texture_path: "sprites/worm_head.png".to_string(),
             color,
         },
         transform,
-        Children::spawn(SpawnIter((0..length).map(move |n_segment| {
-            let segment_position = Vec2::new(-(SEGMENT_WIDTH * n_segment as f32 + HEAD_WIDTH), 0.0);
+        Children::spawn(SpawnIter(
+            (0..length).map(move |n_segment| build_worm_segment(n_segment, color)),
+        )),
+    )
+}
 
-            let transform = Transform::from_translation(
-                segment_position.extend(n_segment as f32 * WORM_Z_DELTA),
-            );
+fn build_worm_segment(n_segment: u32, color: Color) -> impl Bundle {
+    let segment_position = Vec2::new(-(SEGMENT_WIDTH * n_segment as f32 + HEAD_WIDTH), 0.0);
 
-            (
-                WormSegment,
-                SpriteLoader {
-                    texture_path: "sprites/worm_segment.png".to_string(),
-                    color,
-                },
-                transform,
-            )
-        }))),
+    let transform =
+        Transform::from_translation(segment_position.extend(n_segment as f32 * WORM_Z_DELTA));
+
+    (
+        WormSegment,
+        SpriteLoader {
+            texture_path: "sprites/worm_segment.png".to_string(),
+            color,
+        },
+        transform,
+    )
+}
+
+// Replaces the segments of a worm whose length was restored from a save
+pub fn rebuild_worm_segments(mut entity: EntityWorldMut) {
+    let (Some(worm), Some(sprite)) = (entity.get::<Worm>(), entity.get::<SpriteLoader>()) else {
+        return;
+    };
+
+    let length = worm.length;
+    let color = sprite.color;
+
+    entity.despawn_related::<Children>().with_children(|c| {
+        for n_segment in 0..length {
+            c.spawn(build_worm_segment(n_segment, color));
+        }
+    });
+}
+
+pub fn build_worm_nest(position: Vec2) -> impl Bundle {
+    (
+        Name::new("Worm Nest"),
+        WormNest::default(),
+        SpriteLoader {
+            texture_path: "sprites/worm_segment.png".to_string(),
+            color: WORM_NEST_COLOR,
+        },
+        Transform::from_translation(position.extend(WORM_Z - 1.))
+            .with_scale(Vec3::splat(WORM_NEST_SIZE)),
     )
 }
 
 pub fn update_worms(
+    mut commands: Commands,
     time: Res<Time>,
-    mut q_worms: Query<(&mut Worm, &mut Transform, &Children)>,
+    ship: Single<(Entity, &GlobalTransform), With<Ship>>,
+    mut q_worms: Query<(
+        Entity,
+        &mut Worm,
+        &mut Transform,
+        &GlobalTransform,
+        &Children,
+        &SpriteLoader,
+        Option<&WormAggro>,
+    )>,
     mut q_segments: Query<&mut Transform, (With<WormSegment>, Without<Worm>)>,
+    mut q_buildings: Query<
+        (
+            Entity,
+            &GlobalTransform,
+            &mut Inventory,
+            &mut BuildingHealth,
+        ),
+        (With<BuildingHighlight>, Without<Asteroid>),
+    >,
+    mut q_asteroids: Query<
+        (Entity, &Astre, &GlobalTransform, &mut Inventory),
+        (With<Asteroid>, Without<BuildingHighlight>),
+    >,
+    q_parents: Query<&ChildOf>,
 ) {
-    for (mut worm, mut transform, segments) in &mut q_worms {
+    let (ship_entity, ship_global_transform) = *ship;
+    let delta = time.delta_secs();
+
+    for (entity, mut worm, mut transform, global_transform, segments, sprite, aggro) in &mut q_worms
+    {
+        let position = global_transform.translation().truncate();
+
+        // All solar systems are at the origin: only hunt in the worm's own one
+        let solar_system = q_parents.root_ancestor(entity);
+        let same_solar_system = |e: &Entity| q_parents.root_ancestor(*e) == solar_system;
+
+        worm.hunger += delta;
+
+        // Hunt the Ship, or the buildings which attract the worm
+        let prey = std::iter::once((
+            ship_entity,
+            ship_global_transform.translation().truncate(),
+            WORM_AGGRO_RADIUS,
+        ))
+        .chain(q_buildings.iter().map(|(e, g, inventory, _)| {
+            let attraction = (inventory.total_quantity() as f32 * WORM_ATTRACTION_PER_ITEM)
+                .min(WORM_MAX_ATTRACTION_RADIUS);
+            (
+                e,
+                g.translation().truncate(),
+                WORM_AGGRO_RADIUS + attraction,
+            )
+        }))
+        .filter(|(e, p, radius)| p.distance(position) < *radius && same_solar_system(e))
+        .map(|(e, p, _)| (e, p))
+        .min_by(|(_, a), (_, b)| a.distance(position).total_cmp(&b.distance(position)));
+
+        match (prey, aggro) {
+            (Some((prey, _)), aggro) if aggro.is_none_or(|aggro| aggro.0 != prey) => {
+                commands.entity(entity).insert(WormAggro(prey));
+
+                if prey == ship_entity {
+                    commands.trigger(NotificationEvent("A worm is hunting you!".to_string()));
+                }
+            }
+            (None, Some(_)) => {
+                commands.entity(entity).remove::<WormAggro>();
+            }
+            _ => {}
+        }
+
+        // Otherwise, look for asteroids when hungry
+        let food = prey.is_none() && worm.hunger > WORM_HUNGER_THRESHOLD;
+
+        let target = prey.or_else(|| {
+            food.then(|| {
+                q_asteroids
+                    .iter()
+                    .map(|(e, _, g, _)| (e, g.translation().truncate()))
+                    .filter(|(e, p)| {
+                        p.distance(position) < WORM_SMELL_RADIUS && same_solar_system(e)
+                    })
+                    .min_by(|(_, a), (_, b)| a.distance(position).total_cmp(&b.distance(position)))
+            })
+            .flatten()
+        });
+
         if worm
             .change_direction_cooldown
             .tick(time.delta())
             .is_finished()
         {
-            let clamped_angle = PI / 1024.;
-            let add_angle = rand::rng().random_range(0.0..=clamped_angle) - clamped_angle;
+            if let Some((target, target_position)) = target {
+                let angle = transform
+                    .local_x()
+                    .truncate()
+                    .angle_to(target_position - position)
+                    .clamp(-WORM_TURN_ANGLE, WORM_TURN_ANGLE);
+
+                transform.rotate(Quat::from_rotation_z(angle));
+
+                // Bite buildings and asteroids, eating from their inventory
+                let inventory =
+                    if let Ok((_, _, inventory, mut health)) = q_buildings.get_mut(target) {
+                        let bitten = touches_worm_part(global_transform, true, target_position);
+                        if bitten {
+                            health.damage(WORM_BITE_DAMAGE);
+                        }
+                        bitten.then_some(inventory)
+                    } else if let Ok((_, astre, asteroid_global_transform, inventory)) =
+                        q_asteroids.get_mut(target)
+                    {
+                        let asteroid_radius =
+                            astre.surface_radius() * asteroid_global_transform.scale().x;
+                        let head_radius = HEAD_WIDTH / 2. * global_transform.scale().x;
 
-            transform.rotate(Quat::from_rotation_z(add_angle));
+                        (position.distance(target_position) < asteroid_radius + head_radius)
+                            .then_some(inventory)
+                    } else {
+                        None
+                    };
+
+                if let Some(mut inventory) = inventory
+                    && let Some(id) = inventory
+                        .all_ids()
+                        .into_iter()
+                        .max_by_key(|id| inventory.quantity(*id))
+                {
+                    let quantity = inventory.quantity(id).min(WORM_BITE);
+                    inventory.try_remove(id, quantity);
+                    worm.eat(quantity);
+                }
+            } else {
+                let clamped_angle = PI / 1024.;
+                let add_angle = rand::rng().random_range(0.0..=clamped_angle) - clamped_angle;
+
+                transform.rotate(Quat::from_rotation_z(add_angle));
+            }
 
             worm.direction = transform.local_x().truncate();
 
             worm.change_direction_cooldown.reset();
         }
 
+        // Grow when well fed, shrink when starving
+        if worm.eaten >= WORM_GROWTH && worm.length < WORM_MAX_LENGTH {
+            worm.eaten -= WORM_GROWTH;
+            commands
+                .entity(entity)
+                .with_child(build_worm_segment(worm.length, sprite.color));
+            worm.length += 1;
+        } else if worm.hunger > WORM_STARVATION_TIME {
+            worm.hunger = WORM_HUNGER_THRESHOLD;
+
+            if let Some(last_segment) = segments.last()
+                && worm.length > 1
+            {
+                commands.entity(*last_segment).despawn();
+                worm.length -= 1;
+            } else {
+                commands.entity(entity).despawn();
+                continue;
+            }
+        }
+
         let move_progress = worm.change_direction_cooldown.elapsed().as_secs_f32()
             / worm.change_direction_cooldown.duration().as_secs_f32();
 
@@ -122,3 +403,52 @@ pub fn update_worms(
         }
     }
 }
+
+// Killed worms drop their scales
+pub fn kill_worms(mut commands: Commands, q_worms: Query<(Entity, &Worm, &Transform, &ChildOf)>) {
+    for (entity, worm, transform, child_of) in &q_worms {
+        if !worm.is_dead() {
+            continue;
+        }
+
+        let mut inventory = Inventory::new(0);
+        inventory.try_add(ItemId::WormScale, worm.length + 1);
+
+        commands.entity(entity).despawn();
+        commands.entity(child_of.parent()).with_child(build_loot(
+            Transform::from_translation(transform.translation),
+            inventory,
+        ));
+
+        commands.trigger(NotificationEvent("Worm killed".to_string()));
+    }
+}
+
+pub fn update_worm_nests(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut q_nests: Query<(&mut WormNest, &Transform, &ChildOf)>,
+    q_worms: Query<&ChildOf, With<Worm>>,
+) {
+    for (mut nest, transform, child_of) in &mut q_nests {
+        if !nest.spawn_cooldown.tick(time.delta()).just_finished() {
+            continue;
+        }
+
+        let population = q_worms
+            .iter()
+            .filter(|worm_child_of| worm_child_of.parent() == child_of.parent())
+            .count();
+
+        if population < WORM_MAX_POPULATION {
+            let mut rng = StdRng::seed_from_u64(rand::rng().random());
+
+            commands
+                .entity(child_of.parent())
+                .with_child(build_worm_hatchling(
+                    &mut rng,
+                    transform.translation.truncate(),
+                ));
+        }
+    }
+}
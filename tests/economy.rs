@@ -0,0 +1,304 @@
+use astras::{
+    buildings::{
+        AlertKind, BuildingAlert, BuildingCondition, BuildingDisabled, BuildingHealth, Crafter,
+        LogisticFreight, TURRET_AMMO_CAPACITY, place_building,
+    },
+    data::{BuildingId, ItemId, RecipeId},
+    headless::{ONE_MINUTE, ONE_SECOND, headless_app, run_ticks, spawn_ore_planet},
+    items::{
+        Inventory, ItemMap, LogisticJourney, LogisticProvider, LogisticRequest, LogisticScope,
+    },
+};
+use bevy::prelude::*;
+
+fn smelting_foundry(app: &mut App, planet: Entity, position: Vec2) -> Entity {
+    let foundry = place_building(app.world_mut(), planet, BuildingId::Foundry, position);
+    app.world_mut()
+        .get_mut::<Crafter>(foundry)
+        .unwrap()
+        .set_recipe(RecipeId::SmeltElectroniteOre);
+    foundry
+}
+
+fn quantity(app: &App, entity: Entity, id: ItemId) -> u32 {
+    app.world().get::<Inventory>(entity).unwrap().quantity(id)
+}
+
+#[test]
+fn quarry_foundry_and_shuttle_smelt_electronite() {
+    let mut app = headless_app();
+    let planet = spawn_ore_planet(app.world_mut(), 100_000);
+
+    place_building(
+        app.world_mut(),
+        planet,
+        BuildingId::Quarry,
+        Vec2::new(500., 0.),
+    );
+    place_building(
+        app.world_mut(),
+        planet,
+        BuildingId::CargoShuttle,
+        Vec2::ZERO,
+    );
+    let foundry = smelting_foundry(&mut app, planet, Vec2::new(-500., 0.));
+
+    run_ticks(&mut app, 10 * ONE_MINUTE);
+
+    // The shuttle only brings the missing inputs of the current craft
+    let electronite = quantity(&app, foundry, ItemId::Electronite);
+    assert!(electronite >= 60, "only {electronite} Electronite smelted");
+}
+
+#[test]
+fn foundry_without_shuttle_starves() {
+    let mut app = headless_app();
+    let planet = spawn_ore_planet(app.world_mut(), 100_000);
+
+    let quarry = place_building(
+        app.world_mut(),
+        planet,
+        BuildingId::Quarry,
+        Vec2::new(500., 0.),
+    );
+    let foundry = smelting_foundry(&mut app, planet, Vec2::new(-500., 0.));
+
+    run_ticks(&mut app, ONE_MINUTE);
+
+    assert_eq!(quantity(&app, foundry, ItemId::Electronite), 0);
+    assert!(quantity(&app, quarry, ItemId::ElectroniteOre) > 0);
+}
+
+#[test]
+fn damaged_quarry_extracts_less() {
+    let mut app = headless_app();
+    let planet = spawn_ore_planet(app.world_mut(), 100_000);
+
+    let quarry = place_building(app.world_mut(), planet, BuildingId::Quarry, Vec2::ZERO);
+    let damaged_quarry = place_building(app.world_mut(), planet, BuildingId::Quarry, Vec2::ONE);
+    app.world_mut()
+        .get_mut::<BuildingHealth>(damaged_quarry)
+        .unwrap()
+        .damage(50.);
+
+    run_ticks(&mut app, ONE_MINUTE / 6);
+
+    assert!(
+        quantity(&app, damaged_quarry, ItemId::ElectroniteOre)
+            < quantity(&app, quarry, ItemId::ElectroniteOre)
+    );
+}
+
+#[test]
+fn full_warehouse_throttles_quarry() {
+    let mut app = headless_app();
+    let planet = spawn_ore_planet(app.world_mut(), 100_000);
+
+    let warehouse = place_building(
+        app.world_mut(),
+        planet,
+        BuildingId::Warehouse,
+        Vec2::new(500., 0.),
+    );
+    app.world_mut()
+        .entity_mut(warehouse)
+        .insert(Name::new("Stock"));
+    app.world_mut()
+        .get_mut::<Inventory>(warehouse)
+        .unwrap()
+        .try_add(ItemId::ElectroniteOre, 1000);
+
+    let quarry = place_building(app.world_mut(), planet, BuildingId::Quarry, Vec2::ZERO);
+    let condition =
+        BuildingCondition::parse(app.world_mut(), quarry, "Stock ElectroniteOre < 500").unwrap();
+    app.world_mut()
+        .entity_mut(quarry)
+        .insert(condition.unwrap());
+
+    run_ticks(&mut app, ONE_MINUTE / 6);
+
+    assert!(app.world().get::<BuildingDisabled>(quarry).is_some());
+    assert_eq!(quantity(&app, quarry, ItemId::ElectroniteOre), 0);
+
+    app.world_mut()
+        .get_mut::<Inventory>(warehouse)
+        .unwrap()
+        .try_remove(ItemId::ElectroniteOre, 1000);
+    run_ticks(&mut app, ONE_MINUTE / 6);
+
+    assert!(app.world().get::<BuildingDisabled>(quarry).is_none());
+    assert!(quantity(&app, quarry, ItemId::ElectroniteOre) > 0);
+}
+
+#[test]
+fn starving_foundry_raises_alert_until_served() {
+    let mut app = headless_app();
+    let planet = spawn_ore_planet(app.world_mut(), 100_000);
+
+    place_building(
+        app.world_mut(),
+        planet,
+        BuildingId::Quarry,
+        Vec2::new(500., 0.),
+    );
+    let foundry = smelting_foundry(&mut app, planet, Vec2::new(-500., 0.));
+
+    run_ticks(&mut app, ONE_MINUTE / 2);
+    let alert = app.world().get::<BuildingAlert>(foundry).unwrap();
+    assert_eq!(alert.kind(), AlertKind::UnfulfilledRequest);
+    assert!(!alert.is_raised());
+
+    run_ticks(&mut app, ONE_MINUTE);
+    assert!(
+        app.world()
+            .get::<BuildingAlert>(foundry)
+            .unwrap()
+            .is_raised()
+    );
+
+    place_building(
+        app.world_mut(),
+        planet,
+        BuildingId::CargoShuttle,
+        Vec2::ZERO,
+    );
+    run_ticks(&mut app, 2 * ONE_MINUTE);
+
+    // The foundry may wait for a delivery, but not for a whole minute
+    assert!(
+        app.world()
+            .get::<BuildingAlert>(foundry)
+            .is_none_or(|alert| !alert.is_raised())
+    );
+}
+
+#[test]
+fn request_comes_back_when_condition_is_met_again() {
+    let mut app = headless_app();
+    let planet = spawn_ore_planet(app.world_mut(), 100_000);
+
+    let stock = place_building(app.world_mut(), planet, BuildingId::Warehouse, Vec2::ZERO);
+    app.world_mut().entity_mut(stock).insert(Name::new("Stock"));
+
+    let warehouse = place_building(app.world_mut(), planet, BuildingId::Warehouse, Vec2::ONE);
+    let condition =
+        BuildingCondition::parse(app.world_mut(), warehouse, "Stock ElectroniteOre < 500").unwrap();
+    app.world_mut().entity_mut(warehouse).insert((
+        condition.unwrap(),
+        LogisticRequest::new(
+            ItemMap::from_iter([(ItemId::ElectroniteOre, 100)]),
+            LogisticScope::Planet,
+        ),
+    ));
+
+    app.world_mut()
+        .get_mut::<Inventory>(stock)
+        .unwrap()
+        .try_add(ItemId::ElectroniteOre, 1000);
+    run_ticks(&mut app, 1);
+    assert!(app.world().get::<LogisticRequest>(warehouse).is_none());
+
+    app.world_mut()
+        .get_mut::<Inventory>(stock)
+        .unwrap()
+        .try_remove(ItemId::ElectroniteOre, 1000);
+    run_ticks(&mut app, 1);
+
+    let request = app.world().get::<LogisticRequest>(warehouse).unwrap();
+    assert_eq!(request.items().get(&ItemId::ElectroniteOre), Some(&100));
+}
+
+#[test]
+fn freight_on_its_way_skips_a_provider_disabled_meanwhile() {
+    let mut app = headless_app();
+    let planet = spawn_ore_planet(app.world_mut(), 100_000);
+
+    let stock = place_building(app.world_mut(), planet, BuildingId::Warehouse, Vec2::ZERO);
+    app.world_mut().entity_mut(stock).insert(Name::new("Stock"));
+
+    let quarry = place_building(
+        app.world_mut(),
+        planet,
+        BuildingId::Quarry,
+        Vec2::new(3000., 0.),
+    );
+    app.world_mut()
+        .get_mut::<Inventory>(quarry)
+        .unwrap()
+        .try_add(ItemId::ElectroniteOre, 50);
+    let condition =
+        BuildingCondition::parse(app.world_mut(), quarry, "Stock ElectroniteOre < 500").unwrap();
+    app.world_mut()
+        .entity_mut(quarry)
+        .insert(condition.unwrap());
+
+    smelting_foundry(&mut app, planet, Vec2::new(-3000., 0.));
+    let shuttle = place_building(
+        app.world_mut(),
+        planet,
+        BuildingId::CargoShuttle,
+        Vec2::new(-3000., 0.),
+    );
+
+    let provider = |app: &App| {
+        app.world()
+            .get::<LogisticFreight>(shuttle)
+            .unwrap()
+            .logistic_journey()
+            .map(LogisticJourney::provider)
+    };
+
+    while provider(&app) != Some(quarry) {
+        run_ticks(&mut app, 1);
+    }
+
+    app.world_mut()
+        .get_mut::<Inventory>(stock)
+        .unwrap()
+        .try_add(ItemId::ElectroniteOre, 1000);
+    run_ticks(&mut app, ONE_MINUTE);
+
+    // The shuttle turns to the stock instead
+    assert!(app.world().get::<BuildingDisabled>(quarry).is_some());
+    assert_ne!(provider(&app), Some(quarry));
+    assert!(
+        app.world()
+            .get::<LogisticProvider>(quarry)
+            .unwrap()
+            .freights
+            .is_empty()
+    );
+}
+
+#[test]
+fn turret_requests_ammo_until_refilled() {
+    let mut app = headless_app();
+    let planet = spawn_ore_planet(app.world_mut(), 0);
+    let turret = place_building(app.world_mut(), planet, BuildingId::Turret, Vec2::ZERO);
+
+    let mut set_ammo = |ammo: u32| {
+        let mut inventory = app.world_mut().get_mut::<Inventory>(turret).unwrap();
+        let current = inventory.quantity(ItemId::TurretAmmo);
+        inventory.try_remove(ItemId::TurretAmmo, current);
+        inventory.try_add(ItemId::TurretAmmo, ammo);
+        run_ticks(&mut app, ONE_SECOND);
+        app.world()
+            .get::<LogisticRequest>(turret)
+            .map(|request| request.items().clone())
+    };
+
+    assert_eq!(set_ammo(150), None);
+    assert_eq!(
+        set_ammo(99),
+        Some(ItemMap::from([(
+            ItemId::TurretAmmo,
+            TURRET_AMMO_CAPACITY - 99
+        )]))
+    );
+
+    // Requested until nearly full, not as soon as the ammo is back above the low mark
+    assert!(set_ammo(300).is_some());
+    assert!(set_ammo(449).is_some());
+    assert_eq!(set_ammo(450), None);
+    assert_eq!(set_ammo(200), None);
+}
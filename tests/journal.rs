@@ -0,0 +1,86 @@
+use astras::{
+    buildings::{Crafter, place_building},
+    data::{BuildingId, RecipeId},
+    headless::{ONE_MINUTE, headless_app, run_ticks, spawn_ore_planet},
+    journal::EventJournal,
+};
+use bevy::prelude::*;
+use serde_json::Value;
+
+#[test]
+fn journal_records_logistics_and_crafts() {
+    let path = std::env::temp_dir().join(format!("astras_journal_{}.jsonl", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    let mut app = headless_app();
+    app.world_mut()
+        .resource_mut::<EventJournal>()
+        .start(&path)
+        .unwrap();
+
+    let planet = spawn_ore_planet(app.world_mut(), 100_000);
+
+    place_building(
+        app.world_mut(),
+        planet,
+        BuildingId::Quarry,
+        Vec2::new(500., 0.),
+    );
+    place_building(
+        app.world_mut(),
+        planet,
+        BuildingId::CargoShuttle,
+        Vec2::ZERO,
+    );
+    let foundry = place_building(
+        app.world_mut(),
+        planet,
+        BuildingId::Foundry,
+        Vec2::new(-500., 0.),
+    );
+    app.world_mut()
+        .get_mut::<Crafter>(foundry)
+        .unwrap()
+        .set_recipe(RecipeId::SmeltElectroniteOre);
+
+    run_ticks(&mut app, 2 * ONE_MINUTE);
+    app.world_mut().resource_mut::<EventJournal>().stop();
+
+    let lines = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let entries = lines
+        .lines()
+        .map(|line| serde_json::from_str::<Value>(line).unwrap())
+        .collect::<Vec<_>>();
+
+    let count = |event: &str| {
+        entries
+            .iter()
+            .filter(|entry| entry["event"] == event)
+            .count()
+    };
+
+    assert_eq!(count("BuildingBuilt"), 3);
+    assert!(count("FreightRegistered") > 0);
+    assert!(count("FreightTransfer") > 0);
+    assert!(count("CraftCompleted") > 0);
+
+    // One line per item actually moved, at most a freight load
+    for transfer in entries
+        .iter()
+        .filter(|entry| entry["event"] == "FreightTransfer")
+    {
+        assert_eq!(transfer["item"], "ElectroniteOre");
+        let quantity = transfer["quantity"].as_u64().unwrap();
+        assert!((1..=100).contains(&quantity), "{quantity} transferred");
+    }
+
+    let craft = entries
+        .iter()
+        .find(|entry| entry["event"] == "CraftCompleted")
+        .unwrap();
+    assert_eq!(craft["recipe"], "SmeltElectroniteOre");
+    assert_eq!(craft["crafter"], foundry.to_bits());
+    assert!(craft["game_time"].as_f64().unwrap() > 0.);
+}
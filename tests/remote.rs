@@ -0,0 +1,120 @@
+use astras::{
+    buildings::{Crafter, place_building},
+    data::{BuildingId, ItemId, RecipeId},
+    headless::{headless_app, spawn_ore_planet},
+    items::LogisticRequest,
+    process_inventory_request, process_place_building_request,
+    process_set_logistic_request_request, process_set_recipe_request,
+};
+use bevy::{prelude::*, remote::BrpResult};
+use serde_json::{Value, json};
+
+fn place_building_request(world: &mut World, astre: Entity, position: [f32; 2]) -> BrpResult {
+    world
+        .run_system_cached_with(
+            process_place_building_request,
+            Some(json!({ "astre": astre, "building": "Foundry", "position": position })),
+        )
+        .unwrap()
+}
+
+#[test]
+fn placed_buildings_start_as_construction_sites() {
+    let mut app = headless_app();
+    let world = app.world_mut();
+    let planet = spawn_ore_planet(world, 1000);
+
+    let response = place_building_request(world, planet, [100.0, 0.0]).unwrap();
+    let site: Entity = serde_json::from_value(response["entity"].clone()).unwrap();
+
+    assert!(world.get::<Crafter>(site).unwrap().is_construction_site());
+    assert_eq!(world.get::<ChildOf>(site).unwrap().parent(), planet);
+
+    // Same placement rules as in game
+    assert!(place_building_request(world, planet, [100_000.0, 0.0]).is_err());
+
+    // Buildings can only be placed on astres
+    assert!(place_building_request(world, site, [0.0, 0.0]).is_err());
+}
+
+#[test]
+fn set_recipe() {
+    let mut app = headless_app();
+    let world = app.world_mut();
+    let planet = spawn_ore_planet(world, 1000);
+
+    let foundry = place_building(world, planet, BuildingId::Foundry, Vec2::ZERO);
+
+    world
+        .run_system_cached_with(
+            process_set_recipe_request,
+            Some(json!({ "entity": foundry, "recipe": "SmeltElectroniteOre" })),
+        )
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(
+        world.get::<Crafter>(foundry).unwrap().recipe(),
+        Some(RecipeId::SmeltElectroniteOre)
+    );
+
+    // Recipes of other buildings are rejected
+    assert!(
+        world
+            .run_system_cached_with(
+                process_set_recipe_request,
+                Some(json!({ "entity": foundry, "recipe": "CraftScanner" })),
+            )
+            .unwrap()
+            .is_err()
+    );
+}
+
+#[test]
+fn inventory_and_logistic_request() {
+    let mut app = headless_app();
+    let world = app.world_mut();
+    let planet = spawn_ore_planet(world, 1000);
+
+    let inventory = world
+        .run_system_cached_with(process_inventory_request, Some(json!({ "entity": planet })))
+        .unwrap()
+        .unwrap();
+    assert_eq!(inventory["items"]["ElectroniteOre"], json!(1000));
+    assert_eq!(inventory["size"], json!(0));
+
+    let foundry = place_building(world, planet, BuildingId::Foundry, Vec2::ZERO);
+
+    world
+        .run_system_cached_with(
+            process_set_logistic_request_request,
+            Some(json!({ "entity": foundry, "items": { "ElectroniteOre": 10 } })),
+        )
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        world
+            .get::<LogisticRequest>(foundry)
+            .unwrap()
+            .items()
+            .get(&ItemId::ElectroniteOre),
+        Some(&10)
+    );
+
+    world
+        .run_system_cached_with(
+            process_set_logistic_request_request,
+            Some(json!({ "entity": foundry, "items": {} })),
+        )
+        .unwrap()
+        .unwrap();
+    assert!(world.get::<LogisticRequest>(foundry).is_none());
+
+    // Missing params
+    assert!(
+        world
+            .run_system_cached_with(process_inventory_request, None::<Value>)
+            .unwrap()
+            .is_err()
+    );
+}
@@ -0,0 +1,53 @@
+use std::path::Path;
+
+use astras::{
+    SCENARIO_EXTENSION, SCENARIOS_DIR, Scenario, ScenarioGoals,
+    headless::{headless_scenario_app, run_ticks},
+};
+use bevy::prelude::*;
+
+const SCENARIOS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/assets");
+
+fn scenario_path(name: &str) -> String {
+    format!("{SCENARIOS}/{SCENARIOS_DIR}/{name}.{SCENARIO_EXTENSION}")
+}
+
+// Every shipped scenario must spawn
+#[test]
+fn scenarios_spawn() {
+    for entry in std::fs::read_dir(format!("{SCENARIOS}/{SCENARIOS_DIR}")).unwrap() {
+        let path = entry.unwrap().path();
+
+        let scenario =
+            Scenario::from_file(&path).unwrap_or_else(|e| panic!("{}: {e}", path.display()));
+
+        let mut world = World::new();
+        scenario
+            .spawn(&mut world)
+            .unwrap_or_else(|e| panic!("{}: {e}", path.display()));
+    }
+}
+
+#[test]
+fn electronite_smelting_completes() {
+    let mut app = headless_scenario_app(Path::new(&scenario_path("electronite_smelting"))).unwrap();
+
+    run_ticks(&mut app, 6000); // 10 minutes
+
+    let goals = app
+        .world_mut()
+        .query::<&ScenarioGoals>()
+        .single(app.world())
+        .unwrap();
+    assert!(goals.completed);
+}
+
+#[test]
+fn unknown_astres_are_rejected() {
+    let scenario: Scenario = bevy::scene::ron::from_str(
+        "(solar_systems: [(position: (0, 0), buildings: [(building: Quarry, astre: 1000)])])",
+    )
+    .unwrap();
+
+    assert!(scenario.spawn(&mut World::new()).is_err());
+}
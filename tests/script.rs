@@ -0,0 +1,193 @@
+use astras::{
+    buildings::{BuildingCondition, BuildingDisabled, BuildingScript, Crafter, place_building},
+    data::{BuildingId, ItemId, RecipeId},
+    headless::{ONE_SECOND, headless_app, run_ticks, spawn_astre},
+    items::{Inventory, LogisticRequest},
+};
+use bevy::prelude::*;
+
+fn foundry_with_script(app: &mut App, source: &str) -> Entity {
+    let planet = spawn_astre(app.world_mut(), 5000., Inventory::new(0));
+    let foundry = place_building(app.world_mut(), planet, BuildingId::Foundry, Vec2::ZERO);
+    app.world_mut()
+        .entity_mut(foundry)
+        .insert(BuildingScript::new(source.to_string()));
+    foundry
+}
+
+#[test]
+fn script_sets_recipe() {
+    let mut app = headless_app();
+    let foundry = foundry_with_script(
+        &mut app,
+        r#"
+            if building.quantity("Electronite") < 10 {
+                building.set_recipe("SmeltElectroniteOre");
+            }
+        "#,
+    );
+
+    run_ticks(&mut app, 2 * ONE_SECOND);
+
+    let world = app.world();
+    assert_eq!(
+        world.get::<Crafter>(foundry).unwrap().recipe(),
+        Some(RecipeId::SmeltElectroniteOre)
+    );
+    assert_eq!(
+        world.get::<BuildingScript>(foundry).unwrap().last_error(),
+        None
+    );
+}
+
+#[test]
+fn script_requests_items_for_named_building() {
+    let mut app = headless_app();
+    let foundry = foundry_with_script(&mut app, "");
+    let planet = app.world().get::<ChildOf>(foundry).unwrap().parent();
+    app.world_mut()
+        .entity_mut(foundry)
+        .insert(Name::new("Smelter"));
+
+    let warehouse = place_building(app.world_mut(), planet, BuildingId::Warehouse, Vec2::ONE);
+    app.world_mut()
+        .entity_mut(warehouse)
+        .insert(BuildingScript::new(
+            r#"
+            if building.quantity_in("Smelter", "Electronite") == 0 {
+                building.request("ElectroniteOre", 4);
+            } else {
+                building.clear_request();
+            }
+        "#
+            .to_string(),
+        ));
+
+    run_ticks(&mut app, 2 * ONE_SECOND);
+
+    assert_eq!(
+        app.world()
+            .get::<LogisticRequest>(warehouse)
+            .unwrap()
+            .items()
+            .get(&ItemId::ElectroniteOre),
+        Some(&4)
+    );
+
+    app.world_mut()
+        .get_mut::<Inventory>(foundry)
+        .unwrap()
+        .try_add(ItemId::Electronite, 1);
+    run_ticks(&mut app, 2 * ONE_SECOND);
+
+    assert!(app.world().get::<LogisticRequest>(warehouse).is_none());
+}
+
+#[test]
+fn script_errors_are_kept() {
+    let mut app = headless_app();
+    let foundry = foundry_with_script(
+        &mut app,
+        r#"building.quantity_in("Nowhere", "Electronite")"#,
+    );
+
+    run_ticks(&mut app, 2 * ONE_SECOND);
+
+    let script = app.world().get::<BuildingScript>(foundry).unwrap();
+    assert!(script.last_error().unwrap().contains("Nowhere"));
+}
+
+#[test]
+fn crafters_cant_request_items() {
+    let mut app = headless_app();
+    let foundry = foundry_with_script(
+        &mut app,
+        r#"
+            building.set_recipe("SmeltElectroniteOre");
+            building.request("Electronite", 4);
+        "#,
+    );
+
+    run_ticks(&mut app, 2 * ONE_SECOND);
+
+    // The crafter keeps requesting the inputs of its recipe
+    let world = app.world();
+    assert!(
+        world
+            .get::<BuildingScript>(foundry)
+            .unwrap()
+            .last_error()
+            .unwrap()
+            .contains("Crafters")
+    );
+    assert!(
+        world
+            .get::<LogisticRequest>(foundry)
+            .is_none_or(|request| !request.items().contains_key(&ItemId::Electronite))
+    );
+}
+
+#[test]
+fn endless_script_is_stopped() {
+    let mut app = headless_app();
+    let foundry = foundry_with_script(&mut app, "loop {}");
+
+    run_ticks(&mut app, 2 * ONE_SECOND);
+
+    assert!(
+        app.world()
+            .get::<BuildingScript>(foundry)
+            .unwrap()
+            .last_error()
+            .is_some()
+    );
+}
+
+#[test]
+fn scripts_cant_import_files() {
+    let path = std::env::temp_dir().join(format!("astras_script_{}.rhai", std::process::id()));
+    std::fs::write(&path, "export const RECIPE = \"SmeltElectroniteOre\";").unwrap();
+
+    let mut app = headless_app();
+    let source = format!(
+        "import \"{}\" as m; building.set_recipe(m::RECIPE);",
+        path.with_extension("").display()
+    );
+    let foundry = foundry_with_script(&mut app, &source);
+
+    run_ticks(&mut app, 2 * ONE_SECOND);
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(
+        app.world()
+            .get::<BuildingScript>(foundry)
+            .unwrap()
+            .last_error()
+            .is_some()
+    );
+    assert_eq!(app.world().get::<Crafter>(foundry).unwrap().recipe(), None);
+}
+
+#[test]
+fn scripts_of_disabled_buildings_are_paused() {
+    let mut app = headless_app();
+    let foundry = foundry_with_script(&mut app, "");
+    let planet = app.world().get::<ChildOf>(foundry).unwrap().parent();
+    app.world_mut()
+        .entity_mut(foundry)
+        .insert(Name::new("Smelter"));
+
+    // Disabled while the foundry has no Electronite
+    let warehouse = place_building(app.world_mut(), planet, BuildingId::Warehouse, Vec2::ONE);
+    let condition =
+        BuildingCondition::parse(app.world_mut(), warehouse, "Smelter Electronite > 0").unwrap();
+    app.world_mut().entity_mut(warehouse).insert((
+        condition.unwrap(),
+        BuildingScript::new(r#"building.request("ElectroniteOre", 4);"#.to_string()),
+    ));
+
+    run_ticks(&mut app, 2 * ONE_SECOND);
+
+    assert!(app.world().get::<BuildingDisabled>(warehouse).is_some());
+    assert!(app.world().get::<LogisticRequest>(warehouse).is_none());
+}
@@ -0,0 +1,137 @@
+use astras::{
+    buildings::{Crafter, place_building},
+    data::{BuildingId, ItemId, RecipeId},
+    headless::{ONE_MINUTE, headless_app, run_ticks, spawn_ore_planet},
+    items::Inventory,
+    statistics::{FlowKind, ProductionStatistics, StatisticsScope, StatisticsWindow},
+};
+use bevy::prelude::*;
+
+fn total(app: &App, scope: StatisticsScope, kind: FlowKind, item: ItemId) -> u32 {
+    app.world()
+        .resource::<ProductionStatistics>()
+        .history(scope)
+        .map_or(0, |history| {
+            history.total(StatisticsWindow::OneMinute, kind, item)
+        })
+}
+
+#[test]
+fn extraction_and_crafting_are_recorded() {
+    let mut app = headless_app();
+    let planet = spawn_ore_planet(app.world_mut(), 100_000);
+
+    let quarry = place_building(app.world_mut(), planet, BuildingId::Quarry, Vec2::ZERO);
+    let foundry = place_building(app.world_mut(), planet, BuildingId::Foundry, Vec2::ONE);
+    let mut foundry_entity = app.world_mut().entity_mut(foundry);
+    foundry_entity
+        .get_mut::<Crafter>()
+        .unwrap()
+        .set_recipe(RecipeId::SmeltElectroniteOre);
+    foundry_entity
+        .get_mut::<Inventory>()
+        .unwrap()
+        .try_add(ItemId::ElectroniteOre, 100);
+
+    run_ticks(&mut app, ONE_MINUTE / 2);
+
+    let extracted = total(
+        &app,
+        StatisticsScope::Building(quarry),
+        FlowKind::Produced,
+        ItemId::ElectroniteOre,
+    );
+    assert!(extracted > 0);
+    assert!(
+        total(
+            &app,
+            StatisticsScope::Global,
+            FlowKind::Produced,
+            ItemId::ElectroniteOre
+        ) >= extracted
+    );
+
+    assert!(
+        total(
+            &app,
+            StatisticsScope::Building(foundry),
+            FlowKind::Consumed,
+            ItemId::ElectroniteOre
+        ) > 0
+    );
+    assert!(
+        total(
+            &app,
+            StatisticsScope::Building(foundry),
+            FlowKind::Produced,
+            ItemId::Electronite
+        ) > 0
+    );
+}
+
+#[test]
+fn old_flows_leave_the_window() {
+    let mut app = headless_app();
+    let planet = spawn_ore_planet(app.world_mut(), 250);
+    let quarry = place_building(app.world_mut(), planet, BuildingId::Quarry, Vec2::ZERO);
+
+    // The astre is emptied in the first seconds
+    run_ticks(&mut app, ONE_MINUTE / 6);
+    assert_eq!(
+        total(
+            &app,
+            StatisticsScope::Building(quarry),
+            FlowKind::Produced,
+            ItemId::ElectroniteOre
+        ),
+        250
+    );
+
+    run_ticks(&mut app, ONE_MINUTE + ONE_MINUTE / 6);
+    assert_eq!(
+        total(
+            &app,
+            StatisticsScope::Building(quarry),
+            FlowKind::Produced,
+            ItemId::ElectroniteOre
+        ),
+        0
+    );
+
+    let history = app
+        .world()
+        .resource::<ProductionStatistics>()
+        .history(StatisticsScope::Global)
+        .unwrap();
+    assert_eq!(
+        history.total(
+            StatisticsWindow::TenMinutes,
+            FlowKind::Produced,
+            ItemId::ElectroniteOre
+        ),
+        250
+    );
+}
+
+#[test]
+fn statistics_export_to_csv() {
+    let mut app = headless_app();
+    let planet = spawn_ore_planet(app.world_mut(), 100_000);
+    place_building(app.world_mut(), planet, BuildingId::Quarry, Vec2::ZERO);
+
+    run_ticks(&mut app, ONE_MINUTE / 6);
+
+    let mut csv = vec![];
+    app.world()
+        .resource::<ProductionStatistics>()
+        .write_csv(&mut csv)
+        .unwrap();
+    let csv = String::from_utf8(csv).unwrap();
+
+    let mut lines = csv.lines();
+    assert_eq!(
+        lines.next(),
+        Some("scope,window,item,produced,consumed,delivered")
+    );
+    assert!(lines.any(|line| line.starts_with("global,1m,ElectroniteOre,")));
+}
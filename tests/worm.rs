@@ -0,0 +1,100 @@
+use astras::{
+    data::ItemId,
+    headless::headless_app,
+    items::Inventory,
+    universe::{
+        Loot, ProceduralId, UnloadSolarSystem, Worm, assign_procedural_ids, build_loot,
+        build_solar_system, build_worm_hatchling, rehydrate_solar_system,
+    },
+};
+use bevy::{prelude::*, reflect::GetPath};
+use rand::{SeedableRng, rngs::StdRng};
+
+fn nb_segments(world: &World, worm: Entity) -> usize {
+    world
+        .get::<Children>(worm)
+        .map_or(0, |children| children.iter().count())
+}
+
+#[test]
+fn worms_survive_unloading() {
+    let mut app = headless_app();
+    let world = app.world_mut();
+
+    let root = world.spawn(build_solar_system([0, 0])).id();
+    assign_procedural_ids(world.entity_mut(root));
+
+    let (worm, id) = world
+        .query::<(Entity, &ProceduralId, &Worm)>()
+        .iter(world)
+        .map(|(entity, id, _)| (entity, *id))
+        .next()
+        .unwrap();
+
+    // A worm which has grown and been shot at
+    let grown = {
+        let mut worm = world.get_mut::<Worm>(worm).unwrap();
+        let length = worm.length() + 2;
+        *worm.path_mut::<u32>("length").unwrap() = length;
+        worm.damage(5.);
+        worm.clone()
+    };
+
+    let hatchling = world
+        .spawn((
+            build_worm_hatchling(&mut StdRng::seed_from_u64(0), Vec2::ZERO),
+            ChildOf(root),
+        ))
+        .id();
+    let hatchling_length = world.get::<Worm>(hatchling).unwrap().length();
+
+    UnloadSolarSystem(root).apply(world);
+    assert!(world.query::<&Worm>().iter(world).next().is_none());
+
+    rehydrate_solar_system(world.entity_mut(root));
+
+    let (worm, restored) = world
+        .query::<(Entity, &ProceduralId, &Worm)>()
+        .iter(world)
+        .find(|(_, worm_id, _)| **worm_id == id)
+        .map(|(entity, _, worm)| (entity, worm.clone()))
+        .unwrap();
+    assert_eq!(restored, grown);
+    assert_eq!(nb_segments(world, worm), grown.length() as usize);
+
+    let hatchlings = world
+        .query_filtered::<Entity, (With<Worm>, Without<ProceduralId>)>()
+        .iter(world)
+        .collect::<Vec<_>>();
+    assert_eq!(hatchlings.len(), 1);
+    assert_eq!(nb_segments(world, hatchlings[0]), hatchling_length as usize);
+}
+
+#[test]
+fn loot_survives_unloading() {
+    let mut app = headless_app();
+    let world = app.world_mut();
+
+    let root = world.spawn(build_solar_system([0, 0])).id();
+    assign_procedural_ids(world.entity_mut(root));
+
+    let mut inventory = Inventory::new(0);
+    inventory.try_add(ItemId::WormScale, 6);
+    world.spawn((
+        build_loot(Transform::from_xyz(100., 200., 0.), inventory),
+        ChildOf(root),
+    ));
+
+    UnloadSolarSystem(root).apply(world);
+    assert!(world.query::<&Loot>().iter(world).next().is_none());
+
+    rehydrate_solar_system(world.entity_mut(root));
+
+    let (inventory, transform, child_of) = world
+        .query_filtered::<(&Inventory, &Transform, &ChildOf), With<Loot>>()
+        .single(world)
+        .unwrap();
+    assert_eq!(inventory.quantity(ItemId::WormScale), 6);
+    assert_eq!(transform.translation, Vec3::new(100., 200., 0.));
+    assert_eq!(child_of.parent(), root);
+}